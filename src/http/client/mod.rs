@@ -0,0 +1,546 @@
+//! A concrete HTTP/2 client implementation built on top of the generic
+//! connection and session layers.
+
+use std::io;
+use std::cmp;
+
+use http::{Header, HttpError, HttpResult, ErrorCode, StreamId};
+use http::frame::{Frame, HeadersFrame, RstStreamFrame, DataFrame, WindowUpdateFrame,
+                   PriorityUpdateFrame, GoawayFrame};
+use http::connection::{HttpConnection, SendFrame, ReceiveFrame, SendStatus,
+                        HttpConnectionSettings, DEFAULT_INITIAL_WINDOW_SIZE,
+                        DEFAULT_MAX_FRAME_SIZE, apply_window_increment};
+use http::session::{Session, SessionState, Stream, DefaultSessionState, Client, GoawayState,
+                     HttpStats};
+use http::priority::Priority;
+
+#[cfg(test)]
+mod tests;
+
+/// The fixed 24-octet client connection preface mandated by RFC 7540
+/// section 3.5, sent by a client as the very first bytes on a new
+/// connection, ahead of the client's SETTINGS frame.
+const PREFACE: &'static [u8] = b"PRI * HTTP/2.0\r\n\r\nSM\r\n\r\n";
+
+/// Writes the client connection preface (the fixed octet sequence followed
+/// by the client's own initial SETTINGS frame, built from `settings`) to
+/// the given writer.
+pub fn write_preface<W: io::Write>(writer: &mut W, settings: &HttpConnectionSettings) -> HttpResult<()> {
+    try!(writer.write_all(PREFACE).map_err(HttpError::IoError));
+    let frame = settings.to_settings_frame();
+    try!(writer.write_all(&frame.into_raw().serialize()).map_err(HttpError::IoError));
+    Ok(())
+}
+
+/// A request that the client wishes to issue: the headers that make it up,
+/// plus the `Stream` instance that will track its outgoing body (if any)
+/// and its incoming response.
+pub struct RequestStream<S: Stream> {
+    pub headers: Vec<Header>,
+    pub stream: S,
+    pub priority: Priority,
+}
+
+/// Tracks a single HTTP/2 connection from the client's point of view: the
+/// set of streams open on it and the logic needed to start new requests and
+/// push already-queued outgoing data onto the wire.
+pub struct ClientConnection<S: Stream> {
+    conn: HttpConnection,
+    pub state: DefaultSessionState<Client, S>,
+    /// The connection-level send flow-control window: shared across every
+    /// stream, in addition to each stream's own window.
+    send_window: i64,
+    /// The largest DATA payload the peer told us (via
+    /// `SETTINGS_MAX_FRAME_SIZE`) it is willing to receive.
+    max_frame_size: u32,
+    /// The stream ID last served by the incremental round-robin in
+    /// `send_next_data`, so that the next call resumes after it rather than
+    /// always starting from the lowest ID (which would starve later
+    /// streams at the same urgency).
+    rr_cursor: Option<StreamId>,
+    /// The settings we advertise to the peer in our own preface.
+    local_settings: HttpConnectionSettings,
+    /// The settings the peer advertised in its own preface, as last updated
+    /// by `expect_settings` (RFC 7540 defaults until then).
+    peer_settings: HttpConnectionSettings,
+    /// Set once we have sent our own GOAWAY (via `go_away`): no further
+    /// requests may be started, though already-open streams are still
+    /// allowed to drain.
+    draining: bool,
+}
+
+impl<S: Stream> ClientConnection<S> {
+    pub fn new() -> ClientConnection<S> {
+        ClientConnection::with_settings(HttpConnectionSettings::new())
+    }
+
+    /// Creates a new `ClientConnection` that will advertise `settings` in
+    /// its own preface.
+    pub fn with_settings(settings: HttpConnectionSettings) -> ClientConnection<S> {
+        let mut state = DefaultSessionState::new();
+        state.set_push_enabled(settings.get_enable_push());
+        ClientConnection {
+            conn: HttpConnection::new(),
+            state: state,
+            send_window: DEFAULT_INITIAL_WINDOW_SIZE,
+            max_frame_size: DEFAULT_MAX_FRAME_SIZE,
+            rr_cursor: None,
+            local_settings: settings,
+            peer_settings: HttpConnectionSettings::new(),
+            draining: false,
+        }
+    }
+
+    /// The settings the peer has advertised so far (RFC 7540 defaults
+    /// until `expect_settings` has read its preface).
+    pub fn peer_settings(&self) -> &HttpConnectionSettings {
+        &self.peer_settings
+    }
+
+    /// Writes this connection's own preface (the fixed octet sequence plus
+    /// its `local_settings`) to `writer`.
+    pub fn write_preface<W: io::Write>(&self, writer: &mut W) -> HttpResult<()> {
+        write_preface(writer, &self.local_settings)
+    }
+
+    /// Reads and acknowledges the server's preface SETTINGS frame, and
+    /// records the values it advertises.
+    pub fn expect_settings<R: ReceiveFrame, Snd: SendFrame>(&mut self,
+                                                             receiver: &mut R,
+                                                             sender: &mut Snd)
+                                                             -> HttpResult<()> {
+        let settings = try!(self.conn.expect_settings(receiver, sender));
+        try!(self.peer_settings.apply(&settings));
+        self.max_frame_size = self.peer_settings.get_max_frame_size();
+        self.state.stats_mut().frames_received.settings += 1;
+        Ok(())
+    }
+
+    /// The connection's aggregated statistics (frame counts, byte totals and
+    /// stream lifecycle counts), updated as frames are sent and received.
+    pub fn stats(&self) -> &HttpStats {
+        self.state.stats()
+    }
+
+    /// Starts a new request: assigns it the next available (odd) stream ID,
+    /// registers it in the session state and sends its HEADERS frame.
+    pub fn start_request<Snd: SendFrame>(&mut self,
+                                         req: RequestStream<S>,
+                                         sender: &mut Snd)
+                                         -> HttpResult<StreamId> {
+        if self.draining {
+            return Err(HttpError::ConnectionGoingAway);
+        }
+        if let Some(max) = self.peer_settings.get_max_concurrent_streams() {
+            // Only streams we ourselves initiated (odd-numbered) count
+            // against the peer's MAX_CONCURRENT_STREAMS; reserved and
+            // server-pushed (even-numbered) streams do not, per RFC 7540
+            // section 5.1.2.
+            let open = self.state
+                            .iter()
+                            .filter(|&(id, s)| id % 2 == 1 && !s.is_closed())
+                            .count();
+            if open as u32 >= max {
+                return Err(HttpError::MaxConcurrentStreamsExceeded);
+            }
+        }
+
+        let end_of_stream = req.stream.is_closed_local();
+        let priority = req.priority;
+        let mut headers = req.headers;
+        if !headers.iter().any(|h| h.name == b"priority") {
+            headers.push(Header::new(b"priority", priority.to_field_value()));
+        }
+        let headers = headers.clone_for_wire();
+
+        let mut stream = req.stream;
+        stream.set_priority(priority);
+        let stream_id = self.state.insert_outgoing(stream);
+
+        // A new stream starts out at the RFC 7540 default window; credit
+        // (or debit) the difference from the peer's negotiated
+        // SETTINGS_INITIAL_WINDOW_SIZE, if any.
+        let window_delta = self.peer_settings.get_initial_window_size() - DEFAULT_INITIAL_WINDOW_SIZE;
+        if window_delta != 0 {
+            let stream = self.state.get_stream_mut(stream_id).unwrap();
+            try!(stream.increment_send_window(window_delta as i32));
+        }
+
+        let mut frame = HeadersFrame::new(headers, stream_id);
+        if end_of_stream {
+            frame.set_end_of_stream();
+        }
+        try!(sender.send_frame(frame.into_raw()));
+
+        let stats = self.state.stats_mut();
+        stats.frames_sent.headers += 1;
+        stats.streams_opened += 1;
+
+        Ok(stream_id)
+    }
+
+    /// Picks which stream `send_next_data` should serve next, per the RFC
+    /// 9218 scheduling recommendation: among the streams that are currently
+    /// sendable (have data queued and are not flow-control blocked), the
+    /// lowest `urgency` wins; ties between non-incremental streams are
+    /// broken by ascending stream ID (so a non-incremental stream is served
+    /// to completion before its same-urgency siblings even start); ties
+    /// between incremental streams are round-robined via `rr_cursor` so
+    /// that none of them is starved by another.
+    fn next_candidate(&self, sendable: &[StreamId]) -> Option<StreamId> {
+        let min_urgency = sendable.iter()
+                                  .map(|id| self.state.get_stream_ref(*id).unwrap().priority().urgency)
+                                  .min();
+        let min_urgency = match min_urgency {
+            Some(u) => u,
+            None => return None,
+        };
+        let at_min: Vec<StreamId> = sendable.iter()
+                                             .cloned()
+                                             .filter(|id| {
+                                                 self.state.get_stream_ref(*id).unwrap().priority().urgency ==
+                                                 min_urgency
+                                             })
+                                             .collect();
+
+        let non_incremental: Vec<StreamId> = at_min.iter()
+                                                     .cloned()
+                                                     .filter(|id| {
+                                                         !self.state
+                                                              .get_stream_ref(*id)
+                                                              .unwrap()
+                                                              .priority()
+                                                              .incremental
+                                                     })
+                                                     .collect();
+        if !non_incremental.is_empty() {
+            return non_incremental.into_iter().min();
+        }
+
+        // Every candidate at this urgency is incremental: round-robin,
+        // resuming just after whichever stream was served last.
+        let mut ordered = at_min;
+        ordered.sort();
+        let start = match self.rr_cursor {
+            Some(cursor) => ordered.iter().position(|id| *id > cursor).unwrap_or(0),
+            None => 0,
+        };
+        ordered.get(start).cloned()
+    }
+
+    /// Sends a single DATA frame's worth of data for (at most) one stream
+    /// that currently has outgoing data queued and is not flow-control
+    /// blocked, chosen according to the streams' RFC 9218 extensible
+    /// priorities (see `next_candidate`). A stream whose connection- or
+    /// stream-level send window is currently exhausted is skipped (it is
+    /// "blocked", not "done"): `send_next_data` only reports `Nothing` once
+    /// every stream with data is either blocked or fully drained.
+    pub fn send_next_data<Snd: SendFrame>(&mut self, sender: &mut Snd) -> HttpResult<SendStatus> {
+        let mut skipped: Vec<StreamId> = Vec::new();
+        loop {
+            let sendable: Vec<StreamId> = self.state
+                                               .iter()
+                                               .filter(|&(id, s)| {
+                                                   !skipped.contains(id) &&
+                                                   s.has_outgoing_data() &&
+                                                   cmp::min(self.send_window, s.send_window()) > 0
+                                               })
+                                               .map(|(id, _)| *id)
+                                               .collect();
+            let id = match self.next_candidate(&sendable) {
+                Some(id) => id,
+                None => return Ok(SendStatus::Nothing),
+            };
+
+            let effective_window = {
+                let stream = self.state.get_stream_ref(id).unwrap();
+                cmp::min(self.send_window, stream.send_window())
+            };
+            let max_len = cmp::min(effective_window as usize, self.max_frame_size as usize);
+            let incremental = self.state.get_stream_ref(id).unwrap().priority().incremental;
+
+            let stream = self.state.get_stream_mut(id).unwrap();
+            let chunk = stream.take_outgoing_data(max_len);
+            if chunk.is_empty() {
+                // Nothing actually came out of this stream even though it
+                // reported queued data; leave it out of the rest of this
+                // call so that it is not re-picked forever.
+                skipped.push(id);
+                continue;
+            }
+            let sent_len = chunk.len() as u32;
+            self.send_window -= sent_len as i64;
+            stream.decrement_send_window(sent_len);
+
+            let mut frame = DataFrame::with_data(id, chunk);
+            if !stream.has_outgoing_data() && stream.is_closed_local() {
+                frame.set_end_of_stream();
+            }
+            try!(sender.send_frame(frame.into_raw()));
+            if incremental {
+                self.rr_cursor = Some(id);
+            }
+
+            let send_window = self.send_window;
+            let stats = self.state.stats_mut();
+            stats.frames_sent.data += 1;
+            stats.data_bytes_sent += sent_len as u64;
+            stats.send_window = send_window;
+
+            return Ok(SendStatus::Sent);
+        }
+    }
+
+    /// Credits a flow-control window in response to an incoming
+    /// `WINDOW_UPDATE` frame: the connection-level window if it arrived on
+    /// stream `0`, or the named stream's window otherwise (a window update
+    /// for a stream we no longer know about is simply ignored).
+    pub fn handle_window_update(&mut self, frame: WindowUpdateFrame) -> HttpResult<()> {
+        if frame.stream_id == 0 {
+            self.send_window = try!(apply_window_increment(self.send_window,
+                                                             frame.increment as i32));
+            let send_window = self.send_window;
+            let stats = self.state.stats_mut();
+            stats.send_window = send_window;
+            stats.frames_received.window_update += 1;
+        } else if let Some(stream) = self.state.get_stream_mut(frame.stream_id) {
+            try!(stream.increment_send_window(frame.increment as i32));
+            self.state.stats_mut().frames_received.window_update += 1;
+        }
+        Ok(())
+    }
+
+    /// Whether this end of the connection has itself started draining
+    /// (i.e. `go_away` has already been called).
+    pub fn is_going_away(&self) -> bool {
+        self.draining
+    }
+
+    /// The peer's GOAWAY, if one has been received so far (via
+    /// `ClientSession::on_goaway`).
+    pub fn peer_goaway(&self) -> Option<&GoawayState> {
+        self.state.goaway()
+    }
+
+    /// Begins a graceful shutdown of the connection: emits our own GOAWAY
+    /// (naming the highest-numbered server-initiated, i.e. pushed, stream
+    /// we are still willing to process) and stops accepting new requests.
+    /// Streams already open continue to be served by `send_next_data` as
+    /// normal until they finish.
+    pub fn go_away<Snd: SendFrame>(&mut self,
+                                   error_code: ErrorCode,
+                                   debug_data: Option<Vec<u8>>,
+                                   sender: &mut Snd)
+                                   -> HttpResult<()> {
+        self.draining = true;
+        let last_stream_id = self.state
+                                  .iter()
+                                  .map(|(id, _)| *id)
+                                  .filter(|id| id % 2 == 0)
+                                  .max()
+                                  .unwrap_or(0);
+        let frame = GoawayFrame::new(last_stream_id, error_code, debug_data.unwrap_or_else(Vec::new));
+        try!(sender.send_frame(frame.into_raw()));
+        self.state.stats_mut().frames_sent.goaway += 1;
+        Ok(())
+    }
+
+    /// Updates the priority of a stream we have already opened, and informs
+    /// the peer of the change by sending a `PRIORITY_UPDATE` frame for it
+    /// (RFC 9218 section 7.1). A stream ID we no longer know about is
+    /// simply ignored, consistent with `handle_window_update`.
+    pub fn update_priority<Snd: SendFrame>(&mut self,
+                                           stream_id: StreamId,
+                                           priority: Priority,
+                                           sender: &mut Snd)
+                                           -> HttpResult<()> {
+        if let Some(stream) = self.state.get_stream_mut(stream_id) {
+            stream.set_priority(priority);
+        } else {
+            return Ok(());
+        }
+        let frame = PriorityUpdateFrame::new(stream_id, priority.to_field_value());
+        try!(sender.send_frame(frame.into_raw()));
+        self.state.stats_mut().frames_sent.priority_update += 1;
+        Ok(())
+    }
+
+    /// Declines a server push previously reserved by a PUSH_PROMISE, by
+    /// sending `RST_STREAM` for the promised stream (`error_code` is
+    /// expected to be `Cancel` or `RefusedStream`) and recording that error
+    /// on the promised stream, if it is still known. The stream itself stays
+    /// in the session state until it is reaped.
+    pub fn cancel_push<Snd: SendFrame>(&mut self,
+                                       promised_stream_id: StreamId,
+                                       error_code: ErrorCode,
+                                       sender: &mut Snd)
+                                       -> HttpResult<()> {
+        if let Some(stream) = self.state.get_stream_mut(promised_stream_id) {
+            stream.set_error(error_code);
+        }
+        let frame = RstStreamFrame::new(promised_stream_id, error_code);
+        try!(sender.send_frame(frame.into_raw()));
+        self.state.stats_mut().frames_sent.rst_stream += 1;
+        Ok(())
+    }
+}
+
+/// A trivial extension used to turn the high-level `Header` list into the
+/// (for now, un-HPACK-encoded) bytes carried by a HEADERS frame.
+///
+/// TODO: replace with real HPACK encoding once the `hpack` crate is wired
+/// in as a dependency.
+trait HeaderListExt {
+    fn clone_for_wire(&self) -> Vec<u8>;
+}
+
+impl HeaderListExt for Vec<Header> {
+    fn clone_for_wire(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        for header in self {
+            buf.extend_from_slice(&header.name);
+            buf.push(b':');
+            buf.extend_from_slice(&header.value);
+            buf.push(b'\n');
+        }
+        buf
+    }
+}
+
+/// The `Session` implementation used by `ClientConnection`: dispatches the
+/// frame-level events it receives to the appropriate stream tracked in the
+/// session state.
+pub struct ClientSession<'a, S, Snd>
+    where S: SessionState + 'a,
+          Snd: SendFrame + 'a
+{
+    state: &'a mut S,
+    #[allow(dead_code)]
+    sender: &'a mut Snd,
+}
+
+impl<'a, S, Snd> ClientSession<'a, S, Snd>
+    where S: SessionState + 'a,
+          Snd: SendFrame + 'a
+{
+    pub fn new(state: &'a mut S, sender: &'a mut Snd) -> ClientSession<'a, S, Snd> {
+        ClientSession {
+            state: state,
+            sender: sender,
+        }
+    }
+}
+
+impl<'a, S, Snd, C> Session<C> for ClientSession<'a, S, Snd>
+    where S: SessionState + 'a,
+          Snd: SendFrame + 'a,
+          C: SendFrame
+{
+    fn new_data_chunk(&mut self, stream_id: StreamId, data: &[u8], _conn: &mut C) -> HttpResult<()> {
+        if let Some(stream) = self.state.get_stream_mut(stream_id) {
+            stream.new_data_chunk(data);
+        }
+        let stats = self.state.stats_mut();
+        stats.frames_received.data += 1;
+        stats.data_bytes_received += data.len() as u64;
+        Ok(())
+    }
+
+    fn new_headers(&mut self,
+                    stream_id: StreamId,
+                    headers: Vec<Header>,
+                    _conn: &mut C)
+                    -> HttpResult<()> {
+        if let Some(stream) = self.state.get_stream_mut(stream_id) {
+            stream.set_headers(headers);
+        }
+        self.state.stats_mut().frames_received.headers += 1;
+        Ok(())
+    }
+
+    fn new_push_promise(&mut self,
+                         associated_stream_id: StreamId,
+                         promised_stream_id: StreamId,
+                         headers: Vec<Header>,
+                         _conn: &mut C)
+                         -> HttpResult<()> {
+        if !self.state.is_push_enabled() {
+            // We advertised SETTINGS_ENABLE_PUSH=0; the peer sending us a
+            // PUSH_PROMISE regardless is a connection error.
+            return Err(HttpError::ProtocolError);
+        }
+        if promised_stream_id == 0 || promised_stream_id % 2 != 0 {
+            return Err(HttpError::ProtocolError);
+        }
+        if self.state.get_stream_ref(associated_stream_id).is_none() {
+            return Err(HttpError::ProtocolError);
+        }
+
+        // The promised stream is reserved (remote): the server may start
+        // sending HEADERS/DATA on it, but the client itself never
+        // originates data for it.
+        let mut promised = S::Stream::new();
+        promised.close_local();
+        promised.set_headers(headers);
+        self.state.insert_stream(promised_stream_id, promised);
+        let stats = self.state.stats_mut();
+        stats.frames_received.push_promise += 1;
+        stats.streams_opened += 1;
+        Ok(())
+    }
+
+    fn end_of_stream(&mut self, stream_id: StreamId, _conn: &mut C) -> HttpResult<()> {
+        if let Some(stream) = self.state.get_stream_mut(stream_id) {
+            stream.close_remote();
+        }
+        self.state.stats_mut().streams_closed += 1;
+        Ok(())
+    }
+
+    fn rst_stream(&mut self,
+                   stream_id: StreamId,
+                   error_code: ErrorCode,
+                   _conn: &mut C)
+                   -> HttpResult<()> {
+        if let Some(stream) = self.state.get_stream_mut(stream_id) {
+            stream.set_error(error_code);
+        }
+        let stats = self.state.stats_mut();
+        stats.frames_received.rst_stream += 1;
+        stats.streams_reset += 1;
+        Ok(())
+    }
+
+    fn on_goaway(&mut self,
+                  last_stream_id: StreamId,
+                  error_code: ErrorCode,
+                  debug_data: Option<Vec<u8>>,
+                  _conn: &mut C)
+                  -> HttpResult<()> {
+        self.state.set_goaway(GoawayState {
+            last_stream_id: last_stream_id,
+            error_code: error_code,
+            debug_data: debug_data,
+        });
+
+        // Streams already accepted by the peer (id <= last_stream_id) are
+        // left alone to run to completion; anything past that point was
+        // never processed and is safe (and expected) to retry elsewhere.
+        let refused: Vec<StreamId> = self.state
+                                          .iter()
+                                          .filter(|&(id, _)| *id > last_stream_id)
+                                          .map(|(id, _)| *id)
+                                          .collect();
+        for id in refused {
+            if let Some(stream) = self.state.get_stream_mut(id) {
+                stream.set_error(ErrorCode::RefusedStream);
+            }
+        }
+
+        let stats = self.state.stats_mut();
+        stats.frames_received.goaway += 1;
+        stats.goaways_received += 1;
+
+        Ok(())
+    }
+}
@@ -5,10 +5,11 @@ use super::{ClientSession, write_preface, RequestStream};
 use http::{Header, ErrorCode, HttpError};
 use http::tests::common::{TestStream, build_mock_client_conn, build_mock_http_conn,
                           MockReceiveFrame, MockSendFrame};
-use http::frame::{SettingsFrame, DataFrame, Frame, RawFrame};
-use http::connection::{HttpFrame, SendStatus};
-use http::session::{Session, SessionState, Stream, DefaultSessionState};
+use http::frame::{SettingsFrame, DataFrame, Frame, RawFrame, WindowUpdateFrame, HttpSetting};
+use http::connection::{HttpFrame, SendStatus, HttpConnectionSettings};
+use http::session::{Session, SessionState, Stream, DefaultSessionState, HttpStats};
 use http::session::Client as ClientMarker;
+use http::priority::Priority;
 
 /// Tests that a client connection is correctly initialized, by reading the
 /// server preface (i.e. a settings frame) as the first frame of the connection.
@@ -47,6 +48,101 @@ fn test_init_client_conn_no_settings() {
     assert!(conn.expect_settings(&mut receiver, &mut sender).is_err());
 }
 
+/// Tests that `expect_settings` records the values the peer advertises in
+/// its own preface SETTINGS frame, and that they take effect (e.g. the max
+/// frame size used to cap outgoing DATA frames).
+#[test]
+fn test_client_conn_expect_settings_records_peer_settings() {
+    let mut settings = SettingsFrame::new();
+    settings.add_setting(HttpSetting::MaxFrameSize(20000));
+    settings.add_setting(HttpSetting::MaxConcurrentStreams(42));
+    let frames = vec![HttpFrame::SettingsFrame(settings)];
+    let mut conn = build_mock_client_conn();
+    let mut sender = MockSendFrame::new();
+    let mut receiver = MockReceiveFrame::new(frames);
+
+    conn.expect_settings(&mut receiver, &mut sender).unwrap();
+
+    assert_eq!(conn.peer_settings().get_max_frame_size(), 20000);
+    assert_eq!(conn.peer_settings().get_max_concurrent_streams(), Some(42));
+}
+
+/// Tests that `expect_settings` rejects a peer-advertised
+/// `SETTINGS_INITIAL_WINDOW_SIZE` above `MAX_WINDOW_SIZE` (2^31 - 1) as a
+/// `FlowControlError`, rather than silently truncating it later when it is
+/// applied to a new stream's send window.
+#[test]
+fn test_client_conn_expect_settings_rejects_oversized_initial_window_size() {
+    let mut settings = SettingsFrame::new();
+    settings.add_setting(HttpSetting::InitialWindowSize(0x80000000));
+    let frames = vec![HttpFrame::SettingsFrame(settings)];
+    let mut conn = build_mock_client_conn();
+    let mut sender = MockSendFrame::new();
+    let mut receiver = MockReceiveFrame::new(frames);
+
+    let res = conn.expect_settings(&mut receiver, &mut sender);
+    assert!(match res {
+        Err(HttpError::FlowControlError) => true,
+        _ => false,
+    });
+}
+
+/// Tests that `start_request` refuses to open a new stream once doing so
+/// would exceed the peer's advertised `MAX_CONCURRENT_STREAMS`.
+#[test]
+fn test_client_conn_start_request_enforces_max_concurrent_streams() {
+    let mut settings = SettingsFrame::new();
+    settings.add_setting(HttpSetting::MaxConcurrentStreams(1));
+    let frames = vec![HttpFrame::SettingsFrame(settings)];
+    let mut conn = build_mock_client_conn();
+    let mut sender = MockSendFrame::new();
+    let mut receiver = MockReceiveFrame::new(frames);
+    conn.expect_settings(&mut receiver, &mut sender).unwrap();
+
+    let first = RequestStream {
+        headers: vec![Header::new(b":method", b"GET")],
+        stream: prepare_stream(None),
+        priority: Priority::default(),
+    };
+    conn.start_request(first, &mut sender).unwrap();
+
+    let second = RequestStream {
+        headers: vec![Header::new(b":method", b"GET")],
+        stream: prepare_stream(None),
+        priority: Priority::default(),
+    };
+    let res = conn.start_request(second, &mut sender);
+    assert!(match res {
+        Err(HttpError::MaxConcurrentStreamsExceeded) => true,
+        _ => false,
+    });
+}
+
+/// Tests that `start_request`'s `MAX_CONCURRENT_STREAMS` check only counts
+/// streams the client itself initiated: an open, server-pushed
+/// (even-numbered) stream must not count against the limit.
+#[test]
+fn test_client_conn_start_request_max_concurrent_streams_excludes_pushed_streams() {
+    let mut settings = SettingsFrame::new();
+    settings.add_setting(HttpSetting::MaxConcurrentStreams(1));
+    let frames = vec![HttpFrame::SettingsFrame(settings)];
+    let mut conn = build_mock_client_conn();
+    let mut sender = MockSendFrame::new();
+    let mut receiver = MockReceiveFrame::new(frames);
+    conn.expect_settings(&mut receiver, &mut sender).unwrap();
+
+    // A reserved, still-open pushed stream (even-numbered) is in the
+    // session state, but does not count against MAX_CONCURRENT_STREAMS.
+    conn.state.insert_stream(2, prepare_stream(None));
+
+    let req = RequestStream {
+        headers: vec![Header::new(b":method", b"GET")],
+        stream: prepare_stream(None),
+        priority: Priority::default(),
+    };
+    assert!(conn.start_request(req, &mut sender).is_ok());
+}
+
 /// A helper function that prepares a `TestStream` with an optional outgoing data stream.
 fn prepare_stream(data: Option<Vec<u8>>) -> TestStream {
     let mut stream = TestStream::new();
@@ -105,6 +201,81 @@ fn test_client_conn_send_next_data() {
     }
 }
 
+/// Tests that `send_next_data` caps each DATA frame to the connection's send window and to
+/// the (default) max frame size, and that a stream whose window is exhausted is reported as
+/// blocked (`Nothing`) rather than done, without losing its remaining queued data.
+#[test]
+fn test_client_conn_send_next_data_respects_flow_control_window() {
+    let mut conn = build_mock_client_conn();
+    let mut sender = MockSendFrame::new();
+    let body = vec![0u8; 70000];
+    conn.state.insert_outgoing(prepare_stream(Some(body)));
+
+    let mut total_sent = 0;
+    loop {
+        match conn.send_next_data(&mut sender).unwrap() {
+            SendStatus::Sent => {
+                let frame = match HttpFrame::from_raw(&sender.sent.pop().unwrap()).unwrap() {
+                    HttpFrame::DataFrame(frame) => frame,
+                    _ => panic!("Expected a Data frame"),
+                };
+                assert!(frame.data.len() <= 16384);
+                total_sent += frame.data.len();
+            }
+            SendStatus::Nothing => break,
+        }
+    }
+    // The entire connection-level window (65535 octets) was used up, but no more: the
+    // stream is blocked, not finished, so its remaining data must still be queued.
+    assert_eq!(total_sent, 65535);
+    assert!(!conn.state.get_stream_ref(1).unwrap().is_closed_local());
+}
+
+/// Tests that crediting the connection- and stream-level windows via `WindowUpdateFrame`s
+/// unblocks a previously flow-control-blocked stream.
+#[test]
+fn test_client_conn_handle_window_update_unblocks_stream() {
+    let mut conn = build_mock_client_conn();
+    let mut sender = MockSendFrame::new();
+    conn.state.insert_outgoing(prepare_stream(Some(vec![0u8; 65535 + 1])));
+
+    // Drain the connection's entire initial window.
+    while let SendStatus::Sent = conn.send_next_data(&mut sender).unwrap() {}
+    assert_eq!(conn.send_next_data(&mut sender).unwrap(), SendStatus::Nothing);
+
+    // Credit both the connection-level and the stream-level window.
+    conn.handle_window_update(WindowUpdateFrame::new(0, 65535)).unwrap();
+    conn.handle_window_update(WindowUpdateFrame::new(1, 65535)).unwrap();
+
+    assert_eq!(conn.send_next_data(&mut sender).unwrap(), SendStatus::Sent);
+}
+
+/// Tests that `handle_window_update` counts a received `WINDOW_UPDATE`
+/// towards `stats().frames_received.window_update`, whether it targets the
+/// connection (stream 0) or an individual stream.
+#[test]
+fn test_client_conn_handle_window_update_tracks_stats() {
+    let mut conn = build_mock_client_conn();
+    conn.state.insert_outgoing(prepare_stream(None));
+
+    conn.handle_window_update(WindowUpdateFrame::new(0, 1)).unwrap();
+    conn.handle_window_update(WindowUpdateFrame::new(1, 1)).unwrap();
+
+    assert_eq!(conn.stats().frames_received.window_update, 2);
+}
+
+/// Tests that crediting a window past 2^31 - 1 is reported as a `FlowControlError`.
+#[test]
+fn test_client_conn_handle_window_update_overflow() {
+    let mut conn = build_mock_client_conn();
+    // The window starts at 65535; crediting i32::MAX on top of that overflows 2^31 - 1.
+    let res = conn.handle_window_update(WindowUpdateFrame::new(0, 2147483647));
+    assert!(match res {
+        Err(HttpError::FlowControlError) => true,
+        _ => false,
+    });
+}
+
 /// Tests that the `ClientConnection::start_request` method correctly starts a new request.
 #[test]
 fn test_client_conn_start_request() {
@@ -118,6 +289,7 @@ fn test_client_conn_start_request() {
                 Header::new(b":method", b"GET"),
             ],
             stream: prepare_stream(None),
+            priority: Priority::default(),
         };
         conn.start_request(stream, &mut sender).unwrap();
 
@@ -144,6 +316,7 @@ fn test_client_conn_start_request() {
                 Header::new(b":method", b"POST"),
             ],
             stream: prepare_stream(Some(vec![1, 2, 3])),
+            priority: Priority::default(),
         };
         conn.start_request(stream, &mut sender).unwrap();
 
@@ -162,6 +335,165 @@ fn test_client_conn_start_request() {
     }
 }
 
+/// Tests that `start_request` adds a `priority` header reflecting the
+/// request's priority, and records it on the stream itself.
+#[test]
+fn test_client_conn_start_request_sets_priority() {
+    let mut conn = build_mock_client_conn();
+    let mut sender = MockSendFrame::new();
+
+    let stream = RequestStream {
+        headers: vec![
+            Header::new(b":method", b"GET"),
+        ],
+        stream: prepare_stream(None),
+        priority: Priority::new(5, true),
+    };
+    conn.start_request(stream, &mut sender).unwrap();
+
+    assert_eq!(conn.state.get_stream_ref(1).unwrap().priority(), Priority::new(5, true));
+    match HttpFrame::from_raw(&sender.sent[0]).unwrap() {
+        HttpFrame::HeadersFrame(ref frame) => {
+            let fragment = String::from_utf8(frame.header_fragment.clone()).unwrap();
+            assert!(fragment.contains("priority:u=5, i"));
+        }
+        _ => panic!("Expected a Headers frame"),
+    };
+}
+
+/// Tests that `start_request` does not inject a second `priority` header
+/// when the caller's own header list already carries one.
+#[test]
+fn test_client_conn_start_request_does_not_duplicate_priority_header() {
+    let mut conn = build_mock_client_conn();
+    let mut sender = MockSendFrame::new();
+
+    let stream = RequestStream {
+        headers: vec![
+            Header::new(b":method", b"GET"),
+            Header::new(b"priority", b"u=2"),
+        ],
+        stream: prepare_stream(None),
+        priority: Priority::new(5, true),
+    };
+    conn.start_request(stream, &mut sender).unwrap();
+
+    match HttpFrame::from_raw(&sender.sent[0]).unwrap() {
+        HttpFrame::HeadersFrame(ref frame) => {
+            let fragment = String::from_utf8(frame.header_fragment.clone()).unwrap();
+            assert_eq!(fragment.matches("priority:").count(), 1);
+            assert!(fragment.contains("priority:u=2"));
+        }
+        _ => panic!("Expected a Headers frame"),
+    };
+}
+
+/// Tests that `send_next_data` serves the lowest-`urgency` stream first,
+/// regardless of the order streams were registered in.
+#[test]
+fn test_client_conn_send_next_data_orders_by_urgency() {
+    let mut conn = build_mock_client_conn();
+    let mut sender = MockSendFrame::new();
+
+    let mut low = prepare_stream(Some(vec![1]));
+    low.set_priority(Priority::new(7, false));
+    let low_id = conn.state.insert_outgoing(low);
+
+    let mut high = prepare_stream(Some(vec![2]));
+    high.set_priority(Priority::new(0, false));
+    let high_id = conn.state.insert_outgoing(high);
+
+    assert!(high_id > low_id);
+    conn.send_next_data(&mut sender).unwrap();
+    match HttpFrame::from_raw(&sender.sent[0]).unwrap() {
+        HttpFrame::DataFrame(ref frame) => assert_eq!(frame.stream_id, high_id),
+        _ => panic!("Expected a Data frame"),
+    };
+}
+
+/// Tests that two same-urgency non-incremental streams are each served to
+/// completion (in ascending stream-ID order) rather than interleaved: the
+/// lower-ID stream, which needs two DATA frames' worth of data, is fully
+/// drained before the other one (which would fit in a single frame) ever
+/// gets a turn.
+#[test]
+fn test_client_conn_send_next_data_non_incremental_runs_to_completion() {
+    let mut conn = build_mock_client_conn();
+    let mut sender = MockSendFrame::new();
+
+    conn.state.insert_outgoing(prepare_stream(Some(vec![0u8; 20000])));
+    conn.state.insert_outgoing(prepare_stream(Some(vec![1, 2, 3, 4, 5])));
+
+    conn.send_next_data(&mut sender).unwrap();
+    conn.send_next_data(&mut sender).unwrap();
+
+    // Stream 1 needed two frames (20000 > the 16384 max frame size) and is
+    // now fully drained, while stream 3 has not been touched at all.
+    assert!(conn.state.get_stream_ref(1).unwrap().is_closed_local());
+    assert!(!conn.state.get_stream_ref(3).unwrap().is_closed_local());
+
+    conn.send_next_data(&mut sender).unwrap();
+    assert!(conn.state.get_stream_ref(3).unwrap().is_closed_local());
+}
+
+/// Tests that two same-urgency incremental streams are round-robined: each
+/// gets one frame's worth served in turn, rather than (like the
+/// non-incremental case) one running all the way to completion first.
+#[test]
+fn test_client_conn_send_next_data_incremental_round_robins() {
+    let mut conn = build_mock_client_conn();
+    let mut sender = MockSendFrame::new();
+
+    let mut a = prepare_stream(Some(vec![0u8; 20000]));
+    a.set_priority(Priority::new(3, true));
+    conn.state.insert_outgoing(a);
+    let mut b = prepare_stream(Some(vec![0u8; 20000]));
+    b.set_priority(Priority::new(3, true));
+    conn.state.insert_outgoing(b);
+
+    conn.send_next_data(&mut sender).unwrap();
+    // Stream 1 got a frame's worth, but (20000 > the 16384 max frame size)
+    // it isn't done yet: the round-robin cursor should move on to stream 3
+    // next, not serve stream 1 again.
+    assert!(!conn.state.get_stream_ref(1).unwrap().is_closed_local());
+    conn.send_next_data(&mut sender).unwrap();
+    match HttpFrame::from_raw(&sender.sent[1]).unwrap() {
+        HttpFrame::DataFrame(ref frame) => assert_eq!(frame.stream_id, 3),
+        _ => panic!("Expected a Data frame"),
+    };
+}
+
+/// Tests that `update_priority` both records the new priority on the
+/// stream and informs the peer via a `PRIORITY_UPDATE` frame.
+#[test]
+fn test_client_conn_update_priority() {
+    let mut conn = build_mock_client_conn();
+    let mut sender = MockSendFrame::new();
+    conn.state.insert_outgoing(prepare_stream(None));
+
+    conn.update_priority(1, Priority::new(1, true), &mut sender).unwrap();
+
+    assert_eq!(conn.state.get_stream_ref(1).unwrap().priority(), Priority::new(1, true));
+    assert_eq!(sender.sent.len(), 1);
+    match HttpFrame::from_raw(&sender.sent[0]).unwrap() {
+        HttpFrame::PriorityUpdateFrame(ref frame) => {
+            assert_eq!(frame.prioritized_stream_id, 1);
+            assert_eq!(frame.priority_field_value, b"u=1, i".to_vec());
+        }
+        _ => panic!("Expected a PriorityUpdate frame"),
+    };
+}
+
+/// Tests that `update_priority` for an unknown stream ID is a no-op rather
+/// than an error (consistent with `handle_window_update`).
+#[test]
+fn test_client_conn_update_priority_unknown_stream() {
+    let mut conn = build_mock_client_conn();
+    let mut sender = MockSendFrame::new();
+    conn.update_priority(99, Priority::new(1, true), &mut sender).unwrap();
+    assert_eq!(sender.sent.len(), 0);
+}
+
 /// Tests that a `ClientSession` notifies the correct stream when the
 /// appropriate callback is invoked.
 ///
@@ -245,34 +577,172 @@ fn test_client_session_on_rst_stream() {
     assert!(state.get_stream_ref(1).map(|stream| stream.errors.len() == 0).unwrap());
 }
 
-/// Tests that the `ClientSession` signals the correct error to client code when told to go
-/// away by the peer.
+/// Tests that a `ClientSession` reserves a promised stream in response to a PUSH_PROMISE
+/// and that the promised stream accepts headers/data like any other response stream.
 #[test]
-fn test_client_session_on_goaway() {
+fn test_client_session_new_push_promise() {
     let mut state = DefaultSessionState::<ClientMarker, TestStream>::new();
+    state.insert_outgoing(TestStream::new());
     let mut conn = build_mock_http_conn();
     let mut sender = MockSendFrame::new();
+
+    let headers = vec![Header::new(b":status", b"200")];
+    {
+        let mut session = ClientSession::new(&mut state, &mut sender);
+        session.new_push_promise(1, 2, headers.clone(), &mut conn).unwrap();
+    }
+    // The promised (even-numbered) stream is now known to the session...
+    assert!(state.get_stream_ref(2).is_some());
+    assert_eq!(state.get_stream_ref(2).unwrap().headers.clone().unwrap(), headers);
+    // ...and accepts DATA like a normal response stream.
+    {
+        let mut session = ClientSession::new(&mut state, &mut sender);
+        session.new_data_chunk(2, &[1, 2, 3], &mut conn).unwrap();
+    }
+    assert_eq!(state.get_stream_ref(2).unwrap().body, vec![1, 2, 3]);
+}
+
+/// Tests that a `ClientSession` rejects an incoming PUSH_PROMISE as a connection error when
+/// the client has advertised `SETTINGS_ENABLE_PUSH=0`.
+#[test]
+fn test_client_session_push_promise_disabled() {
+    let mut state = DefaultSessionState::<ClientMarker, TestStream>::new();
+    state.insert_outgoing(TestStream::new());
+    state.set_push_enabled(false);
+    let mut conn = build_mock_http_conn();
+    let mut sender = MockSendFrame::new();
+
     let res = {
         let mut session = ClientSession::new(&mut state, &mut sender);
-        session.on_goaway(0, ErrorCode::ProtocolError, None, &mut conn)
+        session.new_push_promise(1, 2, vec![], &mut conn)
+    };
+    assert!(match res {
+        Err(HttpError::ProtocolError) => true,
+        _ => false,
+    });
+    assert!(state.get_stream_ref(2).is_none());
+}
+
+/// Tests that `ClientConnection::with_settings` itself propagates
+/// `enable_push(false)` into the session state, rather than relying on the
+/// caller to separately poke `set_push_enabled` on the raw state.
+#[test]
+fn test_client_conn_with_settings_propagates_enable_push() {
+    let settings = HttpConnectionSettings::new().enable_push(false);
+    let mut client_conn: super::ClientConnection<TestStream> =
+        super::ClientConnection::with_settings(settings);
+    client_conn.state.insert_outgoing(TestStream::new());
+    let mut conn = build_mock_http_conn();
+    let mut sender = MockSendFrame::new();
+
+    let res = {
+        let mut session = ClientSession::new(&mut client_conn.state, &mut sender);
+        session.new_push_promise(1, 2, vec![], &mut conn)
     };
-    if let Err(HttpError::PeerConnectionError(err)) = res {
-        assert_eq!(err.error_code(), ErrorCode::ProtocolError);
-        assert_eq!(err.debug_data(), None);
-    } else {
-        panic!("Expected a PeerConnectionError");
+    assert!(match res {
+        Err(HttpError::ProtocolError) => true,
+        _ => false,
+    });
+    assert!(client_conn.state.get_stream_ref(2).is_none());
+}
+
+/// Tests that a `ClientConnection` can decline a server push by sending an `RST_STREAM`
+/// for the promised stream.
+#[test]
+fn test_client_conn_cancel_push() {
+    let mut conn = build_mock_client_conn();
+    let mut sender = MockSendFrame::new();
+    conn.state.insert_stream(2, prepare_stream(None));
+
+    conn.cancel_push(2, ErrorCode::RefusedStream, &mut sender).unwrap();
+
+    assert_eq!(sender.sent.len(), 1);
+    match HttpFrame::from_raw(&sender.sent[0]).unwrap() {
+        HttpFrame::RstStreamFrame(ref frame) => {
+            assert_eq!(frame.stream_id, 2);
+            assert_eq!(frame.error_code, ErrorCode::RefusedStream);
+        }
+        _ => panic!("Expected a RstStream frame"),
+    };
+}
+
+/// Tests that a `ClientSession` handles an incoming GOAWAY gracefully: it
+/// records the shutdown instead of failing outright, lets streams already
+/// accepted by the peer keep running, and reports streams above
+/// `last_stream_id` as refused (so the client can retry them elsewhere).
+#[test]
+fn test_client_session_on_goaway() {
+    let mut state = DefaultSessionState::<ClientMarker, TestStream>::new();
+    state.insert_outgoing(TestStream::new()); // stream 1: already accepted
+    state.insert_outgoing(TestStream::new()); // stream 3: never processed
+    let mut conn = build_mock_http_conn();
+    let mut sender = MockSendFrame::new();
+
+    {
+        let mut session = ClientSession::new(&mut state, &mut sender);
+        session.on_goaway(1, ErrorCode::ProtocolError, Some(vec![1, 2]), &mut conn).unwrap();
     }
+
+    let goaway = state.goaway().unwrap();
+    assert_eq!(goaway.last_stream_id, 1);
+    assert_eq!(goaway.error_code, ErrorCode::ProtocolError);
+    assert_eq!(goaway.debug_data, Some(vec![1, 2]));
+
+    // Stream 1 is left alone to run to completion...
+    assert_eq!(state.get_stream_ref(1).unwrap().errors.len(), 0);
+    // ...but stream 3 is reported as refused.
+    assert_eq!(state.get_stream_ref(3).unwrap().errors, vec![ErrorCode::RefusedStream]);
+}
+
+/// Tests that `ClientConnection::go_away` sends a `GOAWAY` frame, starts
+/// draining (rejecting new requests) and still lets already-open streams
+/// be flushed by `send_next_data`.
+#[test]
+fn test_client_conn_go_away() {
+    let mut conn = build_mock_client_conn();
+    let mut sender = MockSendFrame::new();
+    conn.state.insert_outgoing(prepare_stream(Some(vec![1, 2, 3])));
+
+    assert!(!conn.is_going_away());
+    conn.go_away(ErrorCode::NoError, None, &mut sender).unwrap();
+    assert!(conn.is_going_away());
+
+    assert_eq!(sender.sent.len(), 1);
+    match HttpFrame::from_raw(&sender.sent[0]).unwrap() {
+        HttpFrame::GoawayFrame(ref frame) => {
+            assert_eq!(frame.error_code, ErrorCode::NoError);
+            // No pushed (server-initiated) streams are open, so there is
+            // nothing to name as still being processed.
+            assert_eq!(frame.last_stream_id, 0);
+        }
+        _ => panic!("Expected a Goaway frame"),
+    };
+
+    // New requests are refused now that we are draining...
+    let req = RequestStream {
+        headers: vec![Header::new(b":method", b"GET")],
+        stream: prepare_stream(None),
+        priority: Priority::default(),
+    };
+    assert!(match conn.start_request(req, &mut sender) {
+        Err(HttpError::ConnectionGoingAway) => true,
+        _ => false,
+    });
+
+    // ...but the stream that was already open may still be flushed.
+    assert_eq!(conn.send_next_data(&mut sender).unwrap(), SendStatus::Sent);
 }
 
 /// Tests that the `write_preface` function correctly writes a client preface to
-/// a given `io::Write`.
+/// a given `io::Write`, built from the given `HttpConnectionSettings`.
 #[test]
 fn test_write_preface() {
     // The buffer (`io::Write`) into which we will write the preface.
     let mut written: Vec<u8> = Vec::new();
+    let settings = HttpConnectionSettings::new().max_concurrent_streams(100);
 
     // Do it...
-    write_preface(&mut written).unwrap();
+    write_preface(&mut written, &settings).unwrap();
 
     // The first bytes written to the underlying transport layer are the
     // preface bytes.
@@ -282,6 +752,96 @@ fn test_write_preface() {
     assert_eq!(preface, &written[..preface.len()]);
     let raw = RawFrame::parse(frames_buf).unwrap();
     let frame: SettingsFrame = Frame::from_raw(&raw).unwrap();
-    // ...which was not an ack, but our own settings.
+    // ...which was not an ack, but our own settings, reflecting what we
+    // asked for.
     assert!(!frame.is_ack());
+    assert_eq!(frame, settings.to_settings_frame());
+}
+
+/// Tests that `ClientConnection::stats` reflects HEADERS and DATA frames
+/// sent, along with the stream opened by `start_request`.
+#[test]
+fn test_client_conn_stats_tracks_sent_frames() {
+    let mut conn = build_mock_client_conn();
+    let mut sender = MockSendFrame::new();
+
+    let stream = RequestStream {
+        headers: vec![Header::new(b":method", b"POST")],
+        stream: prepare_stream(Some(vec![1, 2, 3])),
+        priority: Priority::default(),
+    };
+    conn.start_request(stream, &mut sender).unwrap();
+    conn.send_next_data(&mut sender).unwrap();
+
+    let stats = conn.stats();
+    assert_eq!(stats.frames_sent.headers, 1);
+    assert_eq!(stats.frames_sent.data, 1);
+    assert_eq!(stats.data_bytes_sent, 3);
+    assert_eq!(stats.streams_opened, 1);
+}
+
+/// Tests that `stats().send_window` reflects the connection-level window
+/// immediately after a `WINDOW_UPDATE` credits it, not only after the next
+/// `send_next_data` call.
+#[test]
+fn test_client_conn_stats_send_window_updated_by_window_update() {
+    let mut conn = build_mock_client_conn();
+    conn.handle_window_update(WindowUpdateFrame::new(0, 100)).unwrap();
+    assert_eq!(conn.stats().send_window, 65535 + 100);
+}
+
+/// Tests that `ClientConnection::stats` records a `GOAWAY` sent via
+/// `go_away` and a `PRIORITY_UPDATE` sent via `update_priority`.
+#[test]
+fn test_client_conn_stats_tracks_goaway_and_priority_update() {
+    let mut conn = build_mock_client_conn();
+    let mut sender = MockSendFrame::new();
+    conn.state.insert_outgoing(prepare_stream(None));
+
+    conn.update_priority(1, Priority::new(1, true), &mut sender).unwrap();
+    conn.go_away(ErrorCode::NoError, None, &mut sender).unwrap();
+
+    let stats = conn.stats();
+    assert_eq!(stats.frames_sent.priority_update, 1);
+    assert_eq!(stats.frames_sent.goaway, 1);
+}
+
+/// Tests that the `ClientSession` callbacks update the shared `HttpStats`
+/// for received DATA/HEADERS/RST_STREAM/GOAWAY frames and the stream
+/// lifecycle counters they imply.
+#[test]
+fn test_client_session_updates_stats_on_received_frames() {
+    let mut state = DefaultSessionState::<ClientMarker, TestStream>::new();
+    state.insert_outgoing(TestStream::new()); // stream 1
+    state.insert_outgoing(TestStream::new()); // stream 3
+    let mut conn = build_mock_http_conn();
+    let mut sender = MockSendFrame::new();
+
+    {
+        let mut session = ClientSession::new(&mut state, &mut sender);
+        session.new_headers(1, vec![Header::new(b":status", b"200")], &mut conn).unwrap();
+        session.new_data_chunk(1, &[1, 2, 3, 4], &mut conn).unwrap();
+        session.end_of_stream(1, &mut conn).unwrap();
+        session.rst_stream(3, ErrorCode::Cancel, &mut conn).unwrap();
+        session.on_goaway(3, ErrorCode::NoError, None, &mut conn).unwrap();
+    }
+
+    let stats = state.stats();
+    assert_eq!(stats.frames_received.headers, 1);
+    assert_eq!(stats.frames_received.data, 1);
+    assert_eq!(stats.data_bytes_received, 4);
+    assert_eq!(stats.streams_closed, 1);
+    assert_eq!(stats.frames_received.rst_stream, 1);
+    assert_eq!(stats.streams_reset, 1);
+    assert_eq!(stats.frames_received.goaway, 1);
+    assert_eq!(stats.goaways_received, 1);
+}
+
+/// Tests that a freshly created `ClientConnection` reports all-zero stats,
+/// other than the initial connection-level send window.
+#[test]
+fn test_client_conn_stats_initial_state() {
+    let conn: super::ClientConnection<TestStream> = super::ClientConnection::new();
+    let stats = conn.stats();
+    assert_eq!(*stats, HttpStats::new());
 }
@@ -0,0 +1,265 @@
+//! The connection layer: generic helpers shared by client and server
+//! connections for reading/writing frames and performing the handshake
+//! (i.e. the preface SETTINGS exchange).
+
+use http::{HttpError, HttpResult};
+use http::frame::{Frame, RawFrame, SettingsFrame, HttpSetting, DataFrame, HeadersFrame,
+                   RstStreamFrame, GoawayFrame, WindowUpdateFrame, PushPromiseFrame,
+                   PriorityUpdateFrame};
+
+/// The initial size (in octets) of both the connection-level and every
+/// stream-level flow-control send window, per section 6.9.2 of RFC 7540,
+/// until a SETTINGS frame says otherwise.
+pub const DEFAULT_INITIAL_WINDOW_SIZE: i64 = 65535;
+
+/// The largest frame payload an endpoint will emit unless told (via
+/// `SETTINGS_MAX_FRAME_SIZE`) that its peer allows larger ones.
+pub const DEFAULT_MAX_FRAME_SIZE: u32 = 16384;
+
+/// The largest legal value of a flow-control window (2^31 - 1), per section
+/// 6.9 of RFC 7540. Crediting a window past this is a flow-control error.
+pub const MAX_WINDOW_SIZE: i64 = 0x7fffffff;
+
+/// Applies a `WINDOW_UPDATE` increment to a flow-control window, returning
+/// `FlowControlError` if doing so would overflow the legal window range.
+pub fn apply_window_increment(window: i64, increment: i32) -> HttpResult<i64> {
+    let new_window = window + increment as i64;
+    if new_window > MAX_WINDOW_SIZE {
+        Err(HttpError::FlowControlError)
+    } else {
+        Ok(new_window)
+    }
+}
+
+/// A typed view of the values carried by a SETTINGS frame, with the RFC
+/// 7540 defaults filled in for anything not explicitly configured or (on
+/// the peer's side) not yet advertised. Used both to build the SETTINGS
+/// frame a connection sends as part of its preface (via `to_settings_frame`)
+/// and to record the values the peer advertises in its own (via `apply`).
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct HttpConnectionSettings {
+    header_table_size: u32,
+    enable_push: bool,
+    max_concurrent_streams: Option<u32>,
+    initial_window_size: i64,
+    max_frame_size: u32,
+    max_header_list_size: Option<u32>,
+}
+
+impl HttpConnectionSettings {
+    /// The settings a connection assumes are in effect before any SETTINGS
+    /// frame has been sent or received, per RFC 7540 section 6.5.2.
+    pub fn new() -> HttpConnectionSettings {
+        HttpConnectionSettings {
+            header_table_size: 4096,
+            enable_push: true,
+            max_concurrent_streams: None,
+            initial_window_size: DEFAULT_INITIAL_WINDOW_SIZE,
+            max_frame_size: DEFAULT_MAX_FRAME_SIZE,
+            max_header_list_size: None,
+        }
+    }
+
+    pub fn header_table_size(mut self, size: u32) -> HttpConnectionSettings {
+        self.header_table_size = size;
+        self
+    }
+
+    pub fn enable_push(mut self, enable: bool) -> HttpConnectionSettings {
+        self.enable_push = enable;
+        self
+    }
+
+    pub fn max_concurrent_streams(mut self, max: u32) -> HttpConnectionSettings {
+        self.max_concurrent_streams = Some(max);
+        self
+    }
+
+    pub fn initial_window_size(mut self, size: i64) -> HttpConnectionSettings {
+        self.initial_window_size = size;
+        self
+    }
+
+    pub fn max_frame_size(mut self, size: u32) -> HttpConnectionSettings {
+        self.max_frame_size = size;
+        self
+    }
+
+    pub fn max_header_list_size(mut self, size: u32) -> HttpConnectionSettings {
+        self.max_header_list_size = Some(size);
+        self
+    }
+
+    pub fn get_header_table_size(&self) -> u32 {
+        self.header_table_size
+    }
+
+    pub fn get_enable_push(&self) -> bool {
+        self.enable_push
+    }
+
+    pub fn get_max_concurrent_streams(&self) -> Option<u32> {
+        self.max_concurrent_streams
+    }
+
+    pub fn get_initial_window_size(&self) -> i64 {
+        self.initial_window_size
+    }
+
+    pub fn get_max_frame_size(&self) -> u32 {
+        self.max_frame_size
+    }
+
+    pub fn get_max_header_list_size(&self) -> Option<u32> {
+        self.max_header_list_size
+    }
+
+    /// Renders these settings as the `SettingsFrame` a connection should
+    /// send to convey them to its peer.
+    pub fn to_settings_frame(&self) -> SettingsFrame {
+        let mut frame = SettingsFrame::new();
+        frame.add_setting(HttpSetting::HeaderTableSize(self.header_table_size));
+        frame.add_setting(HttpSetting::EnablePush(if self.enable_push { 1 } else { 0 }));
+        if let Some(max) = self.max_concurrent_streams {
+            frame.add_setting(HttpSetting::MaxConcurrentStreams(max));
+        }
+        frame.add_setting(HttpSetting::InitialWindowSize(self.initial_window_size as u32));
+        frame.add_setting(HttpSetting::MaxFrameSize(self.max_frame_size));
+        if let Some(max) = self.max_header_list_size {
+            frame.add_setting(HttpSetting::MaxHeaderListSize(max));
+        }
+        frame
+    }
+
+    /// Updates these settings with whichever values a peer's SETTINGS frame
+    /// mentions, leaving the rest (and any setting the peer chose not to
+    /// re-state) untouched.
+    ///
+    /// Per RFC 7540 section 6.5.2, a `SETTINGS_INITIAL_WINDOW_SIZE` above
+    /// `MAX_WINDOW_SIZE` (2^31 - 1) is a `FlowControlError`.
+    pub fn apply(&mut self, frame: &SettingsFrame) -> HttpResult<()> {
+        for setting in &frame.settings {
+            match *setting {
+                HttpSetting::HeaderTableSize(v) => self.header_table_size = v,
+                HttpSetting::EnablePush(v) => self.enable_push = v != 0,
+                HttpSetting::MaxConcurrentStreams(v) => self.max_concurrent_streams = Some(v),
+                HttpSetting::InitialWindowSize(v) => {
+                    if v as i64 > MAX_WINDOW_SIZE {
+                        return Err(HttpError::FlowControlError);
+                    }
+                    self.initial_window_size = v as i64;
+                }
+                HttpSetting::MaxFrameSize(v) => self.max_frame_size = v,
+                HttpSetting::MaxHeaderListSize(v) => self.max_header_list_size = Some(v),
+                HttpSetting::Unknown(_, _) => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Default for HttpConnectionSettings {
+    fn default() -> HttpConnectionSettings {
+        HttpConnectionSettings::new()
+    }
+}
+
+/// Indicates whether a send operation (e.g. `ClientConnection::send_next_data`)
+/// actually put a frame on the wire.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SendStatus {
+    Sent,
+    Nothing,
+}
+
+/// Implemented by whatever sink a connection writes outgoing frames to.
+pub trait SendFrame {
+    fn send_frame(&mut self, frame: RawFrame) -> HttpResult<()>;
+}
+
+/// Implemented by whatever source a connection reads incoming frames from.
+pub trait ReceiveFrame {
+    fn recv_frame(&mut self) -> HttpResult<RawFrame>;
+}
+
+/// A parsed frame, tagged with its concrete type. Produced by demultiplexing
+/// a `RawFrame` based on its frame type byte.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum HttpFrame {
+    DataFrame(DataFrame),
+    HeadersFrame(HeadersFrame),
+    RstStreamFrame(RstStreamFrame),
+    SettingsFrame(SettingsFrame),
+    GoawayFrame(GoawayFrame),
+    WindowUpdateFrame(WindowUpdateFrame),
+    PushPromiseFrame(PushPromiseFrame),
+    PriorityUpdateFrame(PriorityUpdateFrame),
+}
+
+impl HttpFrame {
+    pub fn from_raw(raw: &RawFrame) -> HttpResult<HttpFrame> {
+        match raw.header.frame_type {
+            0x0 => Ok(HttpFrame::DataFrame(try!(DataFrame::from_raw(raw)))),
+            0x1 => Ok(HttpFrame::HeadersFrame(try!(HeadersFrame::from_raw(raw)))),
+            0x3 => Ok(HttpFrame::RstStreamFrame(try!(RstStreamFrame::from_raw(raw)))),
+            0x4 => Ok(HttpFrame::SettingsFrame(try!(SettingsFrame::from_raw(raw)))),
+            0x5 => Ok(HttpFrame::PushPromiseFrame(try!(PushPromiseFrame::from_raw(raw)))),
+            0x7 => Ok(HttpFrame::GoawayFrame(try!(GoawayFrame::from_raw(raw)))),
+            0x8 => Ok(HttpFrame::WindowUpdateFrame(try!(WindowUpdateFrame::from_raw(raw)))),
+            0x10 => Ok(HttpFrame::PriorityUpdateFrame(try!(PriorityUpdateFrame::from_raw(raw)))),
+            _ => Err(HttpError::InvalidFrame),
+        }
+    }
+
+    pub fn into_raw(self) -> RawFrame {
+        match self {
+            HttpFrame::DataFrame(f) => f.into_raw(),
+            HttpFrame::HeadersFrame(f) => f.into_raw(),
+            HttpFrame::RstStreamFrame(f) => f.into_raw(),
+            HttpFrame::SettingsFrame(f) => f.into_raw(),
+            HttpFrame::GoawayFrame(f) => f.into_raw(),
+            HttpFrame::WindowUpdateFrame(f) => f.into_raw(),
+            HttpFrame::PushPromiseFrame(f) => f.into_raw(),
+            HttpFrame::PriorityUpdateFrame(f) => f.into_raw(),
+        }
+    }
+}
+
+/// Bundles the handshake and frame (de)multiplexing logic shared by every
+/// connection, regardless of whether it plays the client or server role.
+pub struct HttpConnection;
+
+impl HttpConnection {
+    pub fn new() -> HttpConnection {
+        HttpConnection
+    }
+
+    /// Reads the peer's preface SETTINGS frame (the very first frame on a
+    /// freshly established connection) and immediately acknowledges it.
+    /// Any other frame type in that position is a connection error.
+    pub fn expect_settings<R: ReceiveFrame, S: SendFrame>(&mut self,
+                                                           receiver: &mut R,
+                                                           sender: &mut S)
+                                                           -> HttpResult<SettingsFrame> {
+        let raw = try!(receiver.recv_frame());
+        match try!(HttpFrame::from_raw(&raw)) {
+            HttpFrame::SettingsFrame(settings) => {
+                if !settings.is_ack() {
+                    try!(sender.send_frame(SettingsFrame::ack().into_raw()));
+                }
+                Ok(settings)
+            }
+            _ => Err(HttpError::Other("expected a SETTINGS frame")),
+        }
+    }
+}
+
+impl SendFrame for HttpConnection {
+    /// A bare `HttpConnection` has no transport of its own to write to;
+    /// this is only ever used in contexts (e.g. session-level unit tests)
+    /// where the connection is passed purely for its type, not to actually
+    /// move bytes.
+    fn send_frame(&mut self, _frame: RawFrame) -> HttpResult<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,76 @@
+//! Defines the DATA frame (type `0x0`), as specified by section 6.1 of
+//! RFC 7540.
+
+use http::HttpError;
+use super::{Frame, RawFrame, FrameHeader};
+
+const DATA_FRAME_TYPE: u8 = 0x0;
+const END_STREAM_FLAG: u8 = 0x1;
+
+/// A DATA frame, carrying a chunk of a request or response body.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct DataFrame {
+    pub stream_id: u32,
+    pub data: Vec<u8>,
+    flags: u8,
+}
+
+impl DataFrame {
+    pub fn new(stream_id: u32) -> DataFrame {
+        DataFrame {
+            stream_id: stream_id,
+            data: Vec::new(),
+            flags: 0,
+        }
+    }
+
+    pub fn with_data(stream_id: u32, data: Vec<u8>) -> DataFrame {
+        DataFrame {
+            stream_id: stream_id,
+            data: data,
+            flags: 0,
+        }
+    }
+
+    pub fn set_end_of_stream(&mut self) {
+        self.flags |= END_STREAM_FLAG;
+    }
+
+    pub fn is_end_of_stream(&self) -> bool {
+        self.flags & END_STREAM_FLAG == END_STREAM_FLAG
+    }
+}
+
+impl Frame for DataFrame {
+    fn frame_type() -> u8 {
+        DATA_FRAME_TYPE
+    }
+
+    fn from_raw(raw: &RawFrame) -> Result<DataFrame, HttpError> {
+        if raw.header.frame_type != DATA_FRAME_TYPE {
+            return Err(HttpError::InvalidFrame);
+        }
+        if raw.header.stream_id == 0 {
+            return Err(HttpError::ProtocolError);
+        }
+        Ok(DataFrame {
+            stream_id: raw.header.stream_id,
+            data: raw.payload.clone(),
+            flags: raw.header.flags,
+        })
+    }
+
+    fn into_raw(self) -> RawFrame {
+        let header = FrameHeader {
+            length: self.data.len() as u32,
+            frame_type: DATA_FRAME_TYPE,
+            flags: self.flags,
+            stream_id: self.stream_id,
+        };
+        RawFrame::new(header, self.data)
+    }
+
+    fn get_stream_id(&self) -> u32 {
+        self.stream_id
+    }
+}
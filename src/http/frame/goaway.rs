@@ -0,0 +1,72 @@
+//! Defines the GOAWAY frame (type `0x7`), as specified by section 6.8 of
+//! RFC 7540.
+
+use http::{HttpError, ErrorCode};
+use super::{Frame, RawFrame, FrameHeader};
+
+const GOAWAY_FRAME_TYPE: u8 = 0x7;
+
+/// A GOAWAY frame, used by an endpoint to initiate (graceful or otherwise)
+/// shutdown of a connection.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct GoawayFrame {
+    pub last_stream_id: u32,
+    pub error_code: ErrorCode,
+    pub debug_data: Vec<u8>,
+}
+
+impl GoawayFrame {
+    pub fn new(last_stream_id: u32, error_code: ErrorCode, debug_data: Vec<u8>) -> GoawayFrame {
+        GoawayFrame {
+            last_stream_id: last_stream_id,
+            error_code: error_code,
+            debug_data: debug_data,
+        }
+    }
+}
+
+impl Frame for GoawayFrame {
+    fn frame_type() -> u8 {
+        GOAWAY_FRAME_TYPE
+    }
+
+    fn from_raw(raw: &RawFrame) -> Result<GoawayFrame, HttpError> {
+        if raw.header.frame_type != GOAWAY_FRAME_TYPE || raw.payload.len() < 8 {
+            return Err(HttpError::InvalidFrame);
+        }
+        let last_stream_id = (((raw.payload[0] & 0x7f) as u32) << 24) |
+                              ((raw.payload[1] as u32) << 16) |
+                              ((raw.payload[2] as u32) << 8) | (raw.payload[3] as u32);
+        let error = ((raw.payload[4] as u32) << 24) | ((raw.payload[5] as u32) << 16) |
+                    ((raw.payload[6] as u32) << 8) | (raw.payload[7] as u32);
+        Ok(GoawayFrame::new(last_stream_id,
+                             ErrorCode::from_wire_value(error),
+                             raw.payload[8..].to_vec()))
+    }
+
+    fn into_raw(self) -> RawFrame {
+        let mut payload = Vec::with_capacity(8 + self.debug_data.len());
+        let last_stream_id = self.last_stream_id & 0x7fffffff;
+        payload.push((last_stream_id >> 24) as u8);
+        payload.push((last_stream_id >> 16) as u8);
+        payload.push((last_stream_id >> 8) as u8);
+        payload.push(last_stream_id as u8);
+        let error = self.error_code.to_wire_value();
+        payload.push((error >> 24) as u8);
+        payload.push((error >> 16) as u8);
+        payload.push((error >> 8) as u8);
+        payload.push(error as u8);
+        payload.extend_from_slice(&self.debug_data);
+        let header = FrameHeader {
+            length: payload.len() as u32,
+            frame_type: GOAWAY_FRAME_TYPE,
+            flags: 0,
+            stream_id: 0,
+        };
+        RawFrame::new(header, payload)
+    }
+
+    fn get_stream_id(&self) -> u32 {
+        0
+    }
+}
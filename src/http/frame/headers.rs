@@ -0,0 +1,75 @@
+//! Defines the HEADERS frame (type `0x1`), as specified by section 6.2 of
+//! RFC 7540. Note that `solicit` does not itself perform HPACK
+//! encoding/decoding at this layer: the frame simply carries an opaque
+//! header block fragment.
+
+use http::HttpError;
+use super::{Frame, RawFrame, FrameHeader};
+
+const HEADERS_FRAME_TYPE: u8 = 0x1;
+const END_STREAM_FLAG: u8 = 0x1;
+const END_HEADERS_FLAG: u8 = 0x4;
+
+/// A HEADERS frame, carrying a (fragment of a) HPACK-encoded header block.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct HeadersFrame {
+    pub stream_id: u32,
+    pub header_fragment: Vec<u8>,
+    flags: u8,
+}
+
+impl HeadersFrame {
+    pub fn new(header_fragment: Vec<u8>, stream_id: u32) -> HeadersFrame {
+        HeadersFrame {
+            stream_id: stream_id,
+            header_fragment: header_fragment,
+            flags: END_HEADERS_FLAG,
+        }
+    }
+
+    pub fn set_end_of_stream(&mut self) {
+        self.flags |= END_STREAM_FLAG;
+    }
+
+    pub fn is_end_of_stream(&self) -> bool {
+        self.flags & END_STREAM_FLAG == END_STREAM_FLAG
+    }
+
+    pub fn is_end_of_headers(&self) -> bool {
+        self.flags & END_HEADERS_FLAG == END_HEADERS_FLAG
+    }
+}
+
+impl Frame for HeadersFrame {
+    fn frame_type() -> u8 {
+        HEADERS_FRAME_TYPE
+    }
+
+    fn from_raw(raw: &RawFrame) -> Result<HeadersFrame, HttpError> {
+        if raw.header.frame_type != HEADERS_FRAME_TYPE {
+            return Err(HttpError::InvalidFrame);
+        }
+        if raw.header.stream_id == 0 {
+            return Err(HttpError::ProtocolError);
+        }
+        Ok(HeadersFrame {
+            stream_id: raw.header.stream_id,
+            header_fragment: raw.payload.clone(),
+            flags: raw.header.flags,
+        })
+    }
+
+    fn into_raw(self) -> RawFrame {
+        let header = FrameHeader {
+            length: self.header_fragment.len() as u32,
+            frame_type: HEADERS_FRAME_TYPE,
+            flags: self.flags,
+            stream_id: self.stream_id,
+        };
+        RawFrame::new(header, self.header_fragment)
+    }
+
+    fn get_stream_id(&self) -> u32 {
+        self.stream_id
+    }
+}
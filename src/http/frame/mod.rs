@@ -0,0 +1,103 @@
+//! Defines the framing layer: the generic `RawFrame`/`FrameHeader` wire
+//! representation and the `Frame` trait that every concrete frame type
+//! (SETTINGS, DATA, HEADERS, ...) implements to convert to/from it.
+
+use http::HttpResult;
+
+mod settings;
+mod data;
+mod headers;
+mod rst_stream;
+mod goaway;
+mod window_update;
+mod push_promise;
+mod priority_update;
+
+pub use self::settings::{SettingsFrame, HttpSetting};
+pub use self::data::DataFrame;
+pub use self::headers::HeadersFrame;
+pub use self::rst_stream::RstStreamFrame;
+pub use self::goaway::GoawayFrame;
+pub use self::window_update::WindowUpdateFrame;
+pub use self::push_promise::PushPromiseFrame;
+pub use self::priority_update::PriorityUpdateFrame;
+
+/// The 9-octet header that precedes every HTTP/2 frame payload.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct FrameHeader {
+    pub length: u32,
+    pub frame_type: u8,
+    pub flags: u8,
+    pub stream_id: u32,
+}
+
+/// The raw, unparsed representation of a frame: a header plus the payload
+/// bytes that follow it. Concrete frame types are parsed out of (and
+/// serialized back into) a `RawFrame`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct RawFrame {
+    pub header: FrameHeader,
+    pub payload: Vec<u8>,
+}
+
+impl RawFrame {
+    pub fn new(header: FrameHeader, payload: Vec<u8>) -> RawFrame {
+        RawFrame {
+            header: header,
+            payload: payload,
+        }
+    }
+
+    /// Parses a `RawFrame` out of the front of the given buffer, returning it
+    /// (the rest of the buffer, if any, is simply ignored by this helper).
+    pub fn parse(buf: &[u8]) -> HttpResult<RawFrame> {
+        if buf.len() < 9 {
+            return Err(::http::HttpError::InvalidFrame);
+        }
+        let length = ((buf[0] as u32) << 16) | ((buf[1] as u32) << 8) | (buf[2] as u32);
+        let header = FrameHeader {
+            length: length,
+            frame_type: buf[3],
+            flags: buf[4],
+            stream_id: ((buf[5] as u32) << 24) | ((buf[6] as u32) << 16) |
+                       ((buf[7] as u32) << 8) | (buf[8] as u32),
+        };
+        let payload_end = 9 + length as usize;
+        if buf.len() < payload_end {
+            return Err(::http::HttpError::InvalidFrame);
+        }
+        Ok(RawFrame::new(header, buf[9..payload_end].to_vec()))
+    }
+
+    /// Serializes the `RawFrame` (header + payload) into its wire format.
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(9 + self.payload.len());
+        let length = self.header.length;
+        buf.push((length >> 16) as u8);
+        buf.push((length >> 8) as u8);
+        buf.push(length as u8);
+        buf.push(self.header.frame_type);
+        buf.push(self.header.flags);
+        let stream_id = self.header.stream_id;
+        buf.push((stream_id >> 24) as u8);
+        buf.push((stream_id >> 16) as u8);
+        buf.push((stream_id >> 8) as u8);
+        buf.push(stream_id as u8);
+        buf.extend_from_slice(&self.payload);
+        buf
+    }
+}
+
+/// A trait implemented by every concrete frame type, allowing conversion
+/// to and from the generic `RawFrame` wire representation.
+pub trait Frame: Sized {
+    /// The frame type byte assigned to this frame by the HTTP/2 spec.
+    fn frame_type() -> u8;
+    /// Parses a frame of this type out of a `RawFrame`.
+    fn from_raw(raw: &RawFrame) -> HttpResult<Self>;
+    /// Serializes the frame into its `RawFrame` wire representation.
+    fn into_raw(self) -> RawFrame;
+    /// The ID of the stream that the frame is associated with (`0` for
+    /// connection-level frames).
+    fn get_stream_id(&self) -> u32;
+}
@@ -0,0 +1,66 @@
+//! Defines the PRIORITY_UPDATE frame (type `0x10`), as specified by section
+//! 7.1 of RFC 9218: sent on stream `0` to (re)assign the extensible
+//! priority of an already-open stream.
+
+use http::HttpError;
+use super::{Frame, RawFrame, FrameHeader};
+
+const PRIORITY_UPDATE_FRAME_TYPE: u8 = 0x10;
+
+/// A PRIORITY_UPDATE frame. `prioritized_stream_id` names the stream the
+/// new priority applies to; `priority_field_value` is the raw `priority`
+/// structured-field value (e.g. `u=2, i`).
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct PriorityUpdateFrame {
+    pub prioritized_stream_id: u32,
+    pub priority_field_value: Vec<u8>,
+}
+
+impl PriorityUpdateFrame {
+    pub fn new(prioritized_stream_id: u32, priority_field_value: Vec<u8>) -> PriorityUpdateFrame {
+        PriorityUpdateFrame {
+            prioritized_stream_id: prioritized_stream_id,
+            priority_field_value: priority_field_value,
+        }
+    }
+}
+
+impl Frame for PriorityUpdateFrame {
+    fn frame_type() -> u8 {
+        PRIORITY_UPDATE_FRAME_TYPE
+    }
+
+    fn from_raw(raw: &RawFrame) -> Result<PriorityUpdateFrame, HttpError> {
+        if raw.header.frame_type != PRIORITY_UPDATE_FRAME_TYPE || raw.payload.len() < 4 {
+            return Err(HttpError::InvalidFrame);
+        }
+        if raw.header.stream_id != 0 {
+            return Err(HttpError::ProtocolError);
+        }
+        let prioritized_stream_id = (((raw.payload[0] & 0x7f) as u32) << 24) |
+                                     ((raw.payload[1] as u32) << 16) |
+                                     ((raw.payload[2] as u32) << 8) | (raw.payload[3] as u32);
+        Ok(PriorityUpdateFrame::new(prioritized_stream_id, raw.payload[4..].to_vec()))
+    }
+
+    fn into_raw(self) -> RawFrame {
+        let mut payload = Vec::with_capacity(4 + self.priority_field_value.len());
+        let prioritized = self.prioritized_stream_id & 0x7fffffff;
+        payload.push((prioritized >> 24) as u8);
+        payload.push((prioritized >> 16) as u8);
+        payload.push((prioritized >> 8) as u8);
+        payload.push(prioritized as u8);
+        payload.extend_from_slice(&self.priority_field_value);
+        let header = FrameHeader {
+            length: payload.len() as u32,
+            frame_type: PRIORITY_UPDATE_FRAME_TYPE,
+            flags: 0,
+            stream_id: 0,
+        };
+        RawFrame::new(header, payload)
+    }
+
+    fn get_stream_id(&self) -> u32 {
+        0
+    }
+}
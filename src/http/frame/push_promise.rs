@@ -0,0 +1,81 @@
+//! Defines the PUSH_PROMISE frame (type `0x5`), as specified by section 6.6
+//! of RFC 7540: sent by a server on an existing stream to announce a
+//! server-initiated ("pushed") stream before actually sending a response on
+//! it.
+
+use http::HttpError;
+use super::{Frame, RawFrame, FrameHeader};
+
+const PUSH_PROMISE_FRAME_TYPE: u8 = 0x5;
+const END_HEADERS_FLAG: u8 = 0x4;
+
+/// A PUSH_PROMISE frame. `stream_id` is the already-open stream the push is
+/// associated with; `promised_stream_id` is the (even-numbered) stream ID
+/// the server reserves for the pushed response.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct PushPromiseFrame {
+    pub stream_id: u32,
+    pub promised_stream_id: u32,
+    pub header_fragment: Vec<u8>,
+    flags: u8,
+}
+
+impl PushPromiseFrame {
+    pub fn new(stream_id: u32, promised_stream_id: u32, header_fragment: Vec<u8>) -> PushPromiseFrame {
+        PushPromiseFrame {
+            stream_id: stream_id,
+            promised_stream_id: promised_stream_id,
+            header_fragment: header_fragment,
+            flags: END_HEADERS_FLAG,
+        }
+    }
+
+    pub fn is_end_of_headers(&self) -> bool {
+        self.flags & END_HEADERS_FLAG == END_HEADERS_FLAG
+    }
+}
+
+impl Frame for PushPromiseFrame {
+    fn frame_type() -> u8 {
+        PUSH_PROMISE_FRAME_TYPE
+    }
+
+    fn from_raw(raw: &RawFrame) -> Result<PushPromiseFrame, HttpError> {
+        if raw.header.frame_type != PUSH_PROMISE_FRAME_TYPE || raw.payload.len() < 4 {
+            return Err(HttpError::InvalidFrame);
+        }
+        if raw.header.stream_id == 0 {
+            return Err(HttpError::ProtocolError);
+        }
+        let promised_stream_id = (((raw.payload[0] & 0x7f) as u32) << 24) |
+                                  ((raw.payload[1] as u32) << 16) |
+                                  ((raw.payload[2] as u32) << 8) | (raw.payload[3] as u32);
+        Ok(PushPromiseFrame {
+            stream_id: raw.header.stream_id,
+            promised_stream_id: promised_stream_id,
+            header_fragment: raw.payload[4..].to_vec(),
+            flags: raw.header.flags,
+        })
+    }
+
+    fn into_raw(self) -> RawFrame {
+        let mut payload = Vec::with_capacity(4 + self.header_fragment.len());
+        let promised = self.promised_stream_id & 0x7fffffff;
+        payload.push((promised >> 24) as u8);
+        payload.push((promised >> 16) as u8);
+        payload.push((promised >> 8) as u8);
+        payload.push(promised as u8);
+        payload.extend_from_slice(&self.header_fragment);
+        let header = FrameHeader {
+            length: payload.len() as u32,
+            frame_type: PUSH_PROMISE_FRAME_TYPE,
+            flags: self.flags,
+            stream_id: self.stream_id,
+        };
+        RawFrame::new(header, payload)
+    }
+
+    fn get_stream_id(&self) -> u32 {
+        self.stream_id
+    }
+}
@@ -0,0 +1,59 @@
+//! Defines the RST_STREAM frame (type `0x3`), as specified by section 6.4 of
+//! RFC 7540.
+
+use http::{HttpError, ErrorCode};
+use super::{Frame, RawFrame, FrameHeader};
+
+const RST_STREAM_FRAME_TYPE: u8 = 0x3;
+
+/// An RST_STREAM frame, allowing either endpoint to abruptly terminate a
+/// stream, signaling the reason via an `ErrorCode`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct RstStreamFrame {
+    pub stream_id: u32,
+    pub error_code: ErrorCode,
+}
+
+impl RstStreamFrame {
+    pub fn new(stream_id: u32, error_code: ErrorCode) -> RstStreamFrame {
+        RstStreamFrame {
+            stream_id: stream_id,
+            error_code: error_code,
+        }
+    }
+}
+
+impl Frame for RstStreamFrame {
+    fn frame_type() -> u8 {
+        RST_STREAM_FRAME_TYPE
+    }
+
+    fn from_raw(raw: &RawFrame) -> Result<RstStreamFrame, HttpError> {
+        if raw.header.frame_type != RST_STREAM_FRAME_TYPE || raw.payload.len() != 4 {
+            return Err(HttpError::InvalidFrame);
+        }
+        if raw.header.stream_id == 0 {
+            return Err(HttpError::ProtocolError);
+        }
+        let value = ((raw.payload[0] as u32) << 24) | ((raw.payload[1] as u32) << 16) |
+                    ((raw.payload[2] as u32) << 8) | (raw.payload[3] as u32);
+        Ok(RstStreamFrame::new(raw.header.stream_id, ErrorCode::from_wire_value(value)))
+    }
+
+    fn into_raw(self) -> RawFrame {
+        let value = self.error_code.to_wire_value();
+        let payload = vec![(value >> 24) as u8, (value >> 16) as u8, (value >> 8) as u8,
+                            value as u8];
+        let header = FrameHeader {
+            length: 4,
+            frame_type: RST_STREAM_FRAME_TYPE,
+            flags: 0,
+            stream_id: self.stream_id,
+        };
+        RawFrame::new(header, payload)
+    }
+
+    fn get_stream_id(&self) -> u32 {
+        self.stream_id
+    }
+}
@@ -0,0 +1,136 @@
+//! Defines the SETTINGS frame (type `0x4`) and the individual settings it
+//! may carry, as specified by section 6.5 of RFC 7540.
+
+use http::HttpError;
+use super::{Frame, RawFrame, FrameHeader};
+
+const SETTINGS_FRAME_TYPE: u8 = 0x4;
+const ACK_FLAG: u8 = 0x1;
+
+/// An individual `(identifier, value)` pair carried by a SETTINGS frame.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum HttpSetting {
+    HeaderTableSize(u32),
+    EnablePush(u32),
+    MaxConcurrentStreams(u32),
+    InitialWindowSize(u32),
+    MaxFrameSize(u32),
+    MaxHeaderListSize(u32),
+    Unknown(u16, u32),
+}
+
+impl HttpSetting {
+    fn from_wire(id: u16, value: u32) -> HttpSetting {
+        match id {
+            0x1 => HttpSetting::HeaderTableSize(value),
+            0x2 => HttpSetting::EnablePush(value),
+            0x3 => HttpSetting::MaxConcurrentStreams(value),
+            0x4 => HttpSetting::InitialWindowSize(value),
+            0x5 => HttpSetting::MaxFrameSize(value),
+            0x6 => HttpSetting::MaxHeaderListSize(value),
+            other => HttpSetting::Unknown(other, value),
+        }
+    }
+
+    fn to_wire(&self) -> (u16, u32) {
+        match *self {
+            HttpSetting::HeaderTableSize(v) => (0x1, v),
+            HttpSetting::EnablePush(v) => (0x2, v),
+            HttpSetting::MaxConcurrentStreams(v) => (0x3, v),
+            HttpSetting::InitialWindowSize(v) => (0x4, v),
+            HttpSetting::MaxFrameSize(v) => (0x5, v),
+            HttpSetting::MaxHeaderListSize(v) => (0x6, v),
+            HttpSetting::Unknown(id, v) => (id, v),
+        }
+    }
+}
+
+/// The SETTINGS frame: either a list of settings the sender wants to convey
+/// to its peer, or (when the `ACK` flag is set) an acknowledgement of a
+/// previously received SETTINGS frame.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct SettingsFrame {
+    pub settings: Vec<HttpSetting>,
+    flags: u8,
+}
+
+impl SettingsFrame {
+    /// Creates a new, empty SETTINGS frame (i.e. a "ping"-style frame with no
+    /// settings, used e.g. for the initial connection preface).
+    pub fn new() -> SettingsFrame {
+        SettingsFrame {
+            settings: Vec::new(),
+            flags: 0,
+        }
+    }
+
+    /// Creates the SETTINGS ACK frame.
+    pub fn ack() -> SettingsFrame {
+        SettingsFrame {
+            settings: Vec::new(),
+            flags: ACK_FLAG,
+        }
+    }
+
+    pub fn is_ack(&self) -> bool {
+        self.flags & ACK_FLAG == ACK_FLAG
+    }
+
+    pub fn add_setting(&mut self, setting: HttpSetting) {
+        self.settings.push(setting);
+    }
+}
+
+impl Frame for SettingsFrame {
+    fn frame_type() -> u8 {
+        SETTINGS_FRAME_TYPE
+    }
+
+    fn from_raw(raw: &RawFrame) -> Result<SettingsFrame, HttpError> {
+        if raw.header.frame_type != SETTINGS_FRAME_TYPE {
+            return Err(HttpError::InvalidFrame);
+        }
+        let is_ack = raw.header.flags & ACK_FLAG == ACK_FLAG;
+        if is_ack {
+            return Ok(SettingsFrame::ack());
+        }
+        if raw.payload.len() % 6 != 0 {
+            return Err(HttpError::InvalidFrame);
+        }
+        let mut settings = Vec::new();
+        for chunk in raw.payload.chunks(6) {
+            let id = ((chunk[0] as u16) << 8) | (chunk[1] as u16);
+            let value = ((chunk[2] as u32) << 24) | ((chunk[3] as u32) << 16) |
+                        ((chunk[4] as u32) << 8) | (chunk[5] as u32);
+            settings.push(HttpSetting::from_wire(id, value));
+        }
+        Ok(SettingsFrame {
+            settings: settings,
+            flags: raw.header.flags,
+        })
+    }
+
+    fn into_raw(self) -> RawFrame {
+        let mut payload = Vec::new();
+        for setting in &self.settings {
+            let (id, value) = setting.to_wire();
+            payload.push((id >> 8) as u8);
+            payload.push(id as u8);
+            payload.push((value >> 24) as u8);
+            payload.push((value >> 16) as u8);
+            payload.push((value >> 8) as u8);
+            payload.push(value as u8);
+        }
+        let header = FrameHeader {
+            length: payload.len() as u32,
+            frame_type: SETTINGS_FRAME_TYPE,
+            flags: self.flags,
+            stream_id: 0,
+        };
+        RawFrame::new(header, payload)
+    }
+
+    fn get_stream_id(&self) -> u32 {
+        0
+    }
+}
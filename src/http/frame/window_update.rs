@@ -0,0 +1,58 @@
+//! Defines the WINDOW_UPDATE frame (type `0x8`), as specified by section 6.9
+//! of RFC 7540.
+
+use http::HttpError;
+use super::{Frame, RawFrame, FrameHeader};
+
+const WINDOW_UPDATE_FRAME_TYPE: u8 = 0x8;
+
+/// A WINDOW_UPDATE frame, used to communicate a flow-control window
+/// increment, either for an individual stream or (on stream `0`) for the
+/// connection as a whole.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct WindowUpdateFrame {
+    pub stream_id: u32,
+    pub increment: u32,
+}
+
+impl WindowUpdateFrame {
+    pub fn new(stream_id: u32, increment: u32) -> WindowUpdateFrame {
+        WindowUpdateFrame {
+            stream_id: stream_id,
+            increment: increment,
+        }
+    }
+}
+
+impl Frame for WindowUpdateFrame {
+    fn frame_type() -> u8 {
+        WINDOW_UPDATE_FRAME_TYPE
+    }
+
+    fn from_raw(raw: &RawFrame) -> Result<WindowUpdateFrame, HttpError> {
+        if raw.header.frame_type != WINDOW_UPDATE_FRAME_TYPE || raw.payload.len() != 4 {
+            return Err(HttpError::InvalidFrame);
+        }
+        let increment = (((raw.payload[0] & 0x7f) as u32) << 24) |
+                        ((raw.payload[1] as u32) << 16) |
+                        ((raw.payload[2] as u32) << 8) | (raw.payload[3] as u32);
+        Ok(WindowUpdateFrame::new(raw.header.stream_id, increment))
+    }
+
+    fn into_raw(self) -> RawFrame {
+        let increment = self.increment & 0x7fffffff;
+        let payload = vec![(increment >> 24) as u8, (increment >> 16) as u8,
+                            (increment >> 8) as u8, increment as u8];
+        let header = FrameHeader {
+            length: 4,
+            frame_type: WINDOW_UPDATE_FRAME_TYPE,
+            flags: 0,
+            stream_id: self.stream_id,
+        };
+        RawFrame::new(header, payload)
+    }
+
+    fn get_stream_id(&self) -> u32 {
+        self.stream_id
+    }
+}
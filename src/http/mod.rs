@@ -0,0 +1,202 @@
+//! The top-level module for all HTTP/2 functionality provided by `solicit`.
+//!
+//! Exposes the generic framing layer (`http::frame`), the connection
+//! abstraction that multiplexes frames onto streams (`http::connection`),
+//! the session-level traits that client/server implementations hook into
+//! (`http::session`) and a concrete `http::client` implementation.
+
+use std::error;
+use std::fmt;
+use std::io;
+
+pub mod frame;
+pub mod connection;
+pub mod session;
+pub mod client;
+pub mod priority;
+
+#[cfg(test)]
+pub mod tests;
+
+/// A stream ID, as defined by the HTTP/2 spec. Stream ID `0` is reserved for
+/// connection-level control frames (e.g. SETTINGS, GOAWAY, connection-level
+/// WINDOW_UPDATE).
+pub type StreamId = u32;
+
+/// A convenience alias for the `Result` type used throughout the crate.
+pub type HttpResult<T> = Result<T, HttpError>;
+
+/// The error codes defined by section 7 of RFC 7540.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ErrorCode {
+    NoError,
+    ProtocolError,
+    InternalError,
+    FlowControlError,
+    SettingsTimeout,
+    StreamClosed,
+    FrameSizeError,
+    RefusedStream,
+    Cancel,
+    CompressionError,
+    ConnectError,
+    EnhanceYourCalm,
+    InadequateSecurity,
+    Http11Required,
+    Unknown(u32),
+}
+
+impl ErrorCode {
+    pub fn from_wire_value(value: u32) -> ErrorCode {
+        match value {
+            0x0 => ErrorCode::NoError,
+            0x1 => ErrorCode::ProtocolError,
+            0x2 => ErrorCode::InternalError,
+            0x3 => ErrorCode::FlowControlError,
+            0x4 => ErrorCode::SettingsTimeout,
+            0x5 => ErrorCode::StreamClosed,
+            0x6 => ErrorCode::FrameSizeError,
+            0x7 => ErrorCode::RefusedStream,
+            0x8 => ErrorCode::Cancel,
+            0x9 => ErrorCode::CompressionError,
+            0xa => ErrorCode::ConnectError,
+            0xb => ErrorCode::EnhanceYourCalm,
+            0xc => ErrorCode::InadequateSecurity,
+            0xd => ErrorCode::Http11Required,
+            other => ErrorCode::Unknown(other),
+        }
+    }
+
+    pub fn to_wire_value(&self) -> u32 {
+        match *self {
+            ErrorCode::NoError => 0x0,
+            ErrorCode::ProtocolError => 0x1,
+            ErrorCode::InternalError => 0x2,
+            ErrorCode::FlowControlError => 0x3,
+            ErrorCode::SettingsTimeout => 0x4,
+            ErrorCode::StreamClosed => 0x5,
+            ErrorCode::FrameSizeError => 0x6,
+            ErrorCode::RefusedStream => 0x7,
+            ErrorCode::Cancel => 0x8,
+            ErrorCode::CompressionError => 0x9,
+            ErrorCode::ConnectError => 0xa,
+            ErrorCode::EnhanceYourCalm => 0xb,
+            ErrorCode::InadequateSecurity => 0xc,
+            ErrorCode::Http11Required => 0xd,
+            ErrorCode::Unknown(other) => other,
+        }
+    }
+}
+
+/// Carries the `last_stream_id`, `error_code` and optional debug payload that
+/// the peer sent us in a GOAWAY frame (or that we synthesize locally when we
+/// detect a connection-level protocol violation).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RawErrorConnection {
+    last_stream_id: StreamId,
+    error_code: ErrorCode,
+    debug_data: Option<Vec<u8>>,
+}
+
+impl RawErrorConnection {
+    pub fn new(last_stream_id: StreamId,
+               error_code: ErrorCode,
+               debug_data: Option<Vec<u8>>)
+               -> RawErrorConnection {
+        RawErrorConnection {
+            last_stream_id: last_stream_id,
+            error_code: error_code,
+            debug_data: debug_data,
+        }
+    }
+
+    pub fn last_stream_id(&self) -> StreamId {
+        self.last_stream_id
+    }
+
+    pub fn error_code(&self) -> ErrorCode {
+        self.error_code
+    }
+
+    pub fn debug_data(&self) -> Option<&[u8]> {
+        self.debug_data.as_ref().map(|v| &v[..])
+    }
+}
+
+/// The error type used throughout `solicit`.
+#[derive(Debug)]
+pub enum HttpError {
+    /// The underlying transport returned an IO error.
+    IoError(io::Error),
+    /// A frame could not be parsed or violated the framing layer's
+    /// expectations (e.g. an invalid frame length).
+    InvalidFrame,
+    /// The peer signaled (or we detected) a connection-level error. Carries
+    /// enough information to allow a client to decide whether the failed
+    /// requests are safe to retry on a new connection.
+    PeerConnectionError(RawErrorConnection),
+    /// A more generic, locally-detected protocol error (e.g. receiving a
+    /// frame on a stream ID that is not allowed to carry it).
+    ProtocolError,
+    /// The peer (or the local implementation) would have exceeded the
+    /// negotiated flow-control window.
+    FlowControlError,
+    /// A stream-level error, carrying the ID of the affected stream.
+    StreamError(StreamId, ErrorCode),
+    /// Opening a new outgoing stream would exceed the peer's advertised
+    /// `SETTINGS_MAX_CONCURRENT_STREAMS`.
+    MaxConcurrentStreamsExceeded,
+    /// A new stream was requested after the local endpoint itself sent a
+    /// GOAWAY and started draining the connection.
+    ConnectionGoingAway,
+    /// A catch-all for malformed or unexpected state that does not fit any
+    /// of the above.
+    Other(&'static str),
+}
+
+impl fmt::Display for HttpError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", error::Error::description(self))
+    }
+}
+
+impl error::Error for HttpError {
+    fn description(&self) -> &str {
+        match *self {
+            HttpError::IoError(_) => "encountered an IO error",
+            HttpError::InvalidFrame => "received an invalid frame",
+            HttpError::PeerConnectionError(_) => "the peer terminated the connection",
+            HttpError::ProtocolError => "a protocol error was detected",
+            HttpError::FlowControlError => "a flow control error was detected",
+            HttpError::StreamError(_, _) => "a stream-level error was detected",
+            HttpError::MaxConcurrentStreamsExceeded => {
+                "opening the stream would exceed the peer's MAX_CONCURRENT_STREAMS"
+            }
+            HttpError::ConnectionGoingAway => "the connection is going away",
+            HttpError::Other(msg) => msg,
+        }
+    }
+}
+
+impl From<io::Error> for HttpError {
+    fn from(err: io::Error) -> HttpError {
+        HttpError::IoError(err)
+    }
+}
+
+/// A header, represented simply as a pair of byte vectors: no attempt is
+/// made by this layer to interpret the contents (that is HPACK's job).
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Header {
+    pub name: Vec<u8>,
+    pub value: Vec<u8>,
+}
+
+impl Header {
+    pub fn new<N: Into<Vec<u8>>, V: Into<Vec<u8>>>(name: N, value: V) -> Header {
+        Header {
+            name: name.into(),
+            value: value.into(),
+        }
+    }
+}
@@ -0,0 +1,39 @@
+//! The extensible priority scheme defined by RFC 9218: a per-stream
+//! `urgency`/`incremental` pair, carried either as the `priority` request
+//! header field or (to update it mid-stream) in a `PRIORITY_UPDATE` frame.
+
+/// A stream's priority, per RFC 9218 section 4.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Priority {
+    /// Lower values are served first. Valid range is `0..=7`; the default
+    /// is `3`.
+    pub urgency: u8,
+    /// Whether the response is suitable for incremental (round-robin)
+    /// delivery, as opposed to needing to be sent as a contiguous whole.
+    pub incremental: bool,
+}
+
+impl Priority {
+    pub fn new(urgency: u8, incremental: bool) -> Priority {
+        Priority {
+            urgency: urgency,
+            incremental: incremental,
+        }
+    }
+
+    /// Renders the priority as the value of the structured-field `priority`
+    /// header (or the payload of a `PRIORITY_UPDATE` frame), e.g. `u=3, i`.
+    pub fn to_field_value(&self) -> Vec<u8> {
+        if self.incremental {
+            format!("u={}, i", self.urgency).into_bytes()
+        } else {
+            format!("u={}", self.urgency).into_bytes()
+        }
+    }
+}
+
+impl Default for Priority {
+    fn default() -> Priority {
+        Priority::new(3, false)
+    }
+}
@@ -0,0 +1,312 @@
+//! Defines the session-level abstractions that sit on top of the framing
+//! and connection layers: the `Stream` and `SessionState` traits that model
+//! a single logical request/response exchange and the full set of streams
+//! known to a connection, and the `Session` trait that a connection
+//! dispatches frame-level events to (new headers, new data, stream resets,
+//! GOAWAY, ...).
+
+use std::collections::HashMap;
+use std::marker::PhantomData;
+
+use http::{Header, ErrorCode, HttpResult, StreamId};
+use http::connection::{SendFrame, DEFAULT_INITIAL_WINDOW_SIZE};
+use http::priority::Priority;
+
+/// A marker type identifying the client side of a connection: streams it
+/// initiates use odd-numbered stream IDs.
+pub struct Client;
+
+/// A marker type identifying the server side of a connection: streams it
+/// initiates (e.g. for server push) use even-numbered stream IDs.
+pub struct Server;
+
+/// Assigns stream IDs to locally-initiated streams, according to the
+/// parity mandated by RFC 7540 section 5.1.1 for the given connection role.
+pub trait SessionRole {
+    fn initial_id() -> StreamId;
+    fn next_id(current: StreamId) -> StreamId {
+        current + 2
+    }
+}
+
+impl SessionRole for Client {
+    fn initial_id() -> StreamId {
+        1
+    }
+}
+
+impl SessionRole for Server {
+    fn initial_id() -> StreamId {
+        2
+    }
+}
+
+/// Models a single HTTP/2 stream, from the point of view of whichever side
+/// of the connection owns this instance (e.g. the client, tracking a
+/// request it has issued).
+pub trait Stream {
+    /// Creates a new, freshly-opened stream.
+    fn new() -> Self;
+    /// Associates the (now fully received) headers with the stream.
+    fn set_headers(&mut self, headers: Vec<Header>);
+    /// Appends a chunk of body data received for the stream.
+    fn new_data_chunk(&mut self, data: &[u8]);
+    /// Records that the peer (or the local implementation) signaled an
+    /// error on this stream.
+    fn set_error(&mut self, error_code: ErrorCode);
+    /// Indicates that there is no more data to send on this stream (e.g.
+    /// there never was a body, or it has all been queued/sent already).
+    fn close_local(&mut self);
+    /// Indicates that the peer has finished sending data on this stream
+    /// (i.e. its response is now fully received).
+    fn close_remote(&mut self);
+    /// Whether the local half of the stream is closed (no more outgoing
+    /// data will ever be produced).
+    fn is_closed_local(&self) -> bool;
+    /// Whether there is any data currently queued to be sent on the stream.
+    fn has_outgoing_data(&self) -> bool;
+    /// Removes and returns up to `max_size` bytes of the data currently
+    /// queued to be sent on the stream (an empty `Vec` if there is none).
+    /// Implementations are expected to mark the stream as locally closed
+    /// once all queued data has been drained this way.
+    fn take_outgoing_data(&mut self, max_size: usize) -> Vec<u8>;
+    /// Whether the stream is considered fully done and can be reaped.
+    fn is_closed(&self) -> bool;
+
+    /// The stream's current send (outgoing) flow-control window, i.e. the
+    /// number of octets of DATA payload that may still be sent on it.
+    fn send_window(&self) -> i64;
+    /// Decrements the stream's send window by the given number of octets,
+    /// after a DATA frame of that size has actually been sent.
+    fn decrement_send_window(&mut self, by: u32);
+    /// Credits the stream's send window, as requested by an incoming
+    /// `WINDOW_UPDATE` frame for this stream.
+    fn increment_send_window(&mut self, by: i32) -> HttpResult<()>;
+
+    /// The stream's current (RFC 9218) extensible priority.
+    fn priority(&self) -> Priority;
+    /// Updates the stream's priority, e.g. in response to a
+    /// `PRIORITY_UPDATE` frame.
+    fn set_priority(&mut self, priority: Priority);
+}
+
+/// Records that the peer announced (via GOAWAY) that it is going away, and
+/// the details it gave: the highest-numbered stream it will still process
+/// (streams above it must be treated as refused and are safe to retry on a
+/// fresh connection), the reason, and any accompanying debug data.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct GoawayState {
+    pub last_stream_id: StreamId,
+    pub error_code: ErrorCode,
+    pub debug_data: Option<Vec<u8>>,
+}
+
+/// Per-frame-type counters, broken down by direction in `HttpStats`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct FrameCounts {
+    pub data: u64,
+    pub headers: u64,
+    pub rst_stream: u64,
+    pub settings: u64,
+    pub goaway: u64,
+    pub window_update: u64,
+    pub push_promise: u64,
+    pub priority_update: u64,
+}
+
+/// Aggregated connection statistics, mirroring the observability surface
+/// real-world HTTP clients (e.g. neqo's `Stats`/`TransportStats`) expose to
+/// embedders: frame counts by type and direction, DATA byte totals, stream
+/// lifecycle counts and the current connection-level flow-control window.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct HttpStats {
+    pub frames_sent: FrameCounts,
+    pub frames_received: FrameCounts,
+    pub data_bytes_sent: u64,
+    pub data_bytes_received: u64,
+    pub streams_opened: u64,
+    pub streams_closed: u64,
+    pub streams_reset: u64,
+    pub goaways_received: u64,
+    /// The connection-level send flow-control window currently available.
+    pub send_window: i64,
+}
+
+impl HttpStats {
+    pub fn new() -> HttpStats {
+        HttpStats {
+            frames_sent: FrameCounts::default(),
+            frames_received: FrameCounts::default(),
+            data_bytes_sent: 0,
+            data_bytes_received: 0,
+            streams_opened: 0,
+            streams_closed: 0,
+            streams_reset: 0,
+            goaways_received: 0,
+            send_window: DEFAULT_INITIAL_WINDOW_SIZE,
+        }
+    }
+}
+
+impl Default for HttpStats {
+    fn default() -> HttpStats {
+        HttpStats::new()
+    }
+}
+
+/// Keeps track of every stream known to a connection: the ones still open,
+/// the as-yet-unreaped closed ones, and the bookkeeping needed to assign
+/// IDs to new, locally-initiated streams.
+pub trait SessionState {
+    type Stream: Stream;
+
+    /// Adds a new, locally-initiated outgoing stream, returning the ID
+    /// assigned to it.
+    fn insert_outgoing(&mut self, stream: Self::Stream) -> StreamId;
+    /// Inserts a stream under an ID chosen by the peer (used for
+    /// server-initiated promised streams).
+    fn insert_stream(&mut self, id: StreamId, stream: Self::Stream);
+    fn get_stream_ref(&self, id: StreamId) -> Option<&Self::Stream>;
+    fn get_stream_mut(&mut self, id: StreamId) -> Option<&mut Self::Stream>;
+    /// Removes and returns every stream that is currently closed.
+    fn get_closed(&mut self) -> Vec<Self::Stream>;
+    fn iter(&self) -> ::std::collections::hash_map::Iter<StreamId, Self::Stream>;
+
+    /// Whether this endpoint has advertised (via `SETTINGS_ENABLE_PUSH`)
+    /// that it is willing to accept server pushes.
+    fn is_push_enabled(&self) -> bool;
+    /// Updates whether this endpoint accepts server pushes.
+    fn set_push_enabled(&mut self, enabled: bool);
+
+    /// Records that the peer has announced (via GOAWAY) that it is going
+    /// away.
+    fn set_goaway(&mut self, goaway: GoawayState);
+    /// The peer's GOAWAY, if one has been received yet.
+    fn goaway(&self) -> Option<&GoawayState>;
+
+    /// The connection's aggregated statistics.
+    fn stats(&self) -> &HttpStats;
+    /// Mutable access to the connection's aggregated statistics, for
+    /// whichever code (the connection itself, or its `Session`) just
+    /// observed something worth counting.
+    fn stats_mut(&mut self) -> &mut HttpStats;
+}
+
+/// The default, in-memory `SessionState` implementation, backed by a
+/// `HashMap` keyed by stream ID.
+pub struct DefaultSessionState<R: SessionRole, S: Stream> {
+    streams: HashMap<StreamId, S>,
+    next_id: StreamId,
+    push_enabled: bool,
+    goaway: Option<GoawayState>,
+    stats: HttpStats,
+    _role: PhantomData<R>,
+}
+
+impl<R: SessionRole, S: Stream> DefaultSessionState<R, S> {
+    pub fn new() -> DefaultSessionState<R, S> {
+        DefaultSessionState {
+            streams: HashMap::new(),
+            next_id: R::initial_id(),
+            push_enabled: true,
+            goaway: None,
+            stats: HttpStats::new(),
+            _role: PhantomData,
+        }
+    }
+}
+
+impl<R: SessionRole, S: Stream> SessionState for DefaultSessionState<R, S> {
+    type Stream = S;
+
+    fn insert_outgoing(&mut self, stream: S) -> StreamId {
+        let id = self.next_id;
+        self.next_id = R::next_id(id);
+        self.streams.insert(id, stream);
+        id
+    }
+
+    fn insert_stream(&mut self, id: StreamId, stream: S) {
+        self.streams.insert(id, stream);
+    }
+
+    fn get_stream_ref(&self, id: StreamId) -> Option<&S> {
+        self.streams.get(&id)
+    }
+
+    fn get_stream_mut(&mut self, id: StreamId) -> Option<&mut S> {
+        self.streams.get_mut(&id)
+    }
+
+    fn get_closed(&mut self) -> Vec<S> {
+        let closed_ids: Vec<StreamId> = self.streams
+                                             .iter()
+                                             .filter(|&(_, s)| s.is_closed())
+                                             .map(|(id, _)| *id)
+                                             .collect();
+        closed_ids.into_iter().filter_map(|id| self.streams.remove(&id)).collect()
+    }
+
+    fn iter(&self) -> ::std::collections::hash_map::Iter<StreamId, S> {
+        self.streams.iter()
+    }
+
+    fn is_push_enabled(&self) -> bool {
+        self.push_enabled
+    }
+
+    fn set_push_enabled(&mut self, enabled: bool) {
+        self.push_enabled = enabled;
+    }
+
+    fn set_goaway(&mut self, goaway: GoawayState) {
+        self.goaway = Some(goaway);
+    }
+
+    fn goaway(&self) -> Option<&GoawayState> {
+        self.goaway.as_ref()
+    }
+
+    fn stats(&self) -> &HttpStats {
+        &self.stats
+    }
+
+    fn stats_mut(&mut self) -> &mut HttpStats {
+        &mut self.stats
+    }
+}
+
+/// Receives the frame-level events that a connection dispatches once it has
+/// demultiplexed an incoming frame onto the stream (or connection) that it
+/// applies to. Implementations are generic over the type used to send
+/// frames back out, so that they can react to an event (e.g. by queuing a
+/// `RST_STREAM`) without the connection itself needing to know about it.
+pub trait Session<C: SendFrame> {
+    fn new_data_chunk(&mut self, stream_id: StreamId, data: &[u8], conn: &mut C) -> HttpResult<()>;
+    fn new_headers(&mut self,
+                    stream_id: StreamId,
+                    headers: Vec<Header>,
+                    conn: &mut C)
+                    -> HttpResult<()>;
+    /// Invoked when the peer announces a server push via a PUSH_PROMISE
+    /// frame on `associated_stream_id`, reserving `promised_stream_id` for
+    /// the response it is about to send.
+    fn new_push_promise(&mut self,
+                         associated_stream_id: StreamId,
+                         promised_stream_id: StreamId,
+                         headers: Vec<Header>,
+                         conn: &mut C)
+                         -> HttpResult<()>;
+    fn end_of_stream(&mut self, stream_id: StreamId, conn: &mut C) -> HttpResult<()>;
+    fn rst_stream(&mut self,
+                   stream_id: StreamId,
+                   error_code: ErrorCode,
+                   conn: &mut C)
+                   -> HttpResult<()>;
+    fn on_goaway(&mut self,
+                  last_stream_id: StreamId,
+                  error_code: ErrorCode,
+                  debug_data: Option<Vec<u8>>,
+                  conn: &mut C)
+                  -> HttpResult<()>;
+}
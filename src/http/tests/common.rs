@@ -0,0 +1,168 @@
+//! Mock implementations of the `Stream`, `SendFrame` and `ReceiveFrame`
+//! traits, used by the unit tests throughout the `http` module.
+
+use http::{Header, ErrorCode, HttpResult, HttpError};
+use http::frame::RawFrame;
+use http::connection::{HttpConnection, SendFrame, ReceiveFrame, HttpFrame,
+                        DEFAULT_INITIAL_WINDOW_SIZE, apply_window_increment};
+use http::session::Stream;
+use http::client::ClientConnection;
+use http::priority::Priority;
+
+/// A `Stream` implementation that simply records everything that happens to
+/// it, for inspection by assertions in tests.
+pub struct TestStream {
+    pub body: Vec<u8>,
+    pub headers: Option<Vec<Header>>,
+    pub errors: Vec<ErrorCode>,
+    outgoing: Option<Vec<u8>>,
+    closed_local: bool,
+    closed: bool,
+    send_window: i64,
+    priority: Priority,
+}
+
+impl TestStream {
+    /// Queues the given data to be returned (at most `max_size` bytes at a
+    /// time) by subsequent `take_outgoing_data` calls, simulating a stream
+    /// with a request body still to be sent.
+    pub fn set_outgoing(&mut self, data: Vec<u8>) {
+        self.outgoing = Some(data);
+    }
+}
+
+impl Stream for TestStream {
+    fn new() -> TestStream {
+        TestStream {
+            body: Vec::new(),
+            headers: None,
+            errors: Vec::new(),
+            outgoing: None,
+            closed_local: false,
+            closed: false,
+            send_window: DEFAULT_INITIAL_WINDOW_SIZE,
+            priority: Priority::default(),
+        }
+    }
+
+    fn set_headers(&mut self, headers: Vec<Header>) {
+        self.headers = Some(headers);
+    }
+
+    fn new_data_chunk(&mut self, data: &[u8]) {
+        self.body.extend_from_slice(data);
+    }
+
+    fn set_error(&mut self, error_code: ErrorCode) {
+        self.errors.push(error_code);
+        self.closed = true;
+    }
+
+    fn close_local(&mut self) {
+        self.closed_local = true;
+    }
+
+    fn close_remote(&mut self) {
+        self.closed = true;
+    }
+
+    fn is_closed_local(&self) -> bool {
+        self.closed_local
+    }
+
+    fn has_outgoing_data(&self) -> bool {
+        self.outgoing.as_ref().map(|d| !d.is_empty()).unwrap_or(false)
+    }
+
+    fn take_outgoing_data(&mut self, max_size: usize) -> Vec<u8> {
+        let data = match self.outgoing.take() {
+            Some(data) => data,
+            None => return Vec::new(),
+        };
+        if data.len() <= max_size {
+            self.closed_local = true;
+            data
+        } else {
+            let mut remaining = data;
+            let chunk = remaining.drain(..max_size).collect();
+            self.outgoing = Some(remaining);
+            chunk
+        }
+    }
+
+    fn is_closed(&self) -> bool {
+        self.closed
+    }
+
+    fn send_window(&self) -> i64 {
+        self.send_window
+    }
+
+    fn decrement_send_window(&mut self, by: u32) {
+        self.send_window -= by as i64;
+    }
+
+    fn increment_send_window(&mut self, by: i32) -> HttpResult<()> {
+        self.send_window = try!(apply_window_increment(self.send_window, by));
+        Ok(())
+    }
+
+    fn priority(&self) -> Priority {
+        self.priority
+    }
+
+    fn set_priority(&mut self, priority: Priority) {
+        self.priority = priority;
+    }
+}
+
+/// A `SendFrame` sink that simply buffers every raw frame it is given, so
+/// that tests can assert on exactly what was sent.
+pub struct MockSendFrame {
+    pub sent: Vec<RawFrame>,
+}
+
+impl MockSendFrame {
+    pub fn new() -> MockSendFrame {
+        MockSendFrame { sent: Vec::new() }
+    }
+}
+
+impl SendFrame for MockSendFrame {
+    fn send_frame(&mut self, frame: RawFrame) -> HttpResult<()> {
+        self.sent.push(frame);
+        Ok(())
+    }
+}
+
+/// A `ReceiveFrame` source that yields a fixed, pre-recorded list of frames.
+pub struct MockReceiveFrame {
+    pub recv_list: Vec<HttpFrame>,
+}
+
+impl MockReceiveFrame {
+    pub fn new(frames: Vec<HttpFrame>) -> MockReceiveFrame {
+        MockReceiveFrame { recv_list: frames }
+    }
+}
+
+impl ReceiveFrame for MockReceiveFrame {
+    fn recv_frame(&mut self) -> HttpResult<RawFrame> {
+        if self.recv_list.is_empty() {
+            return Err(HttpError::Other("no more frames queued"));
+        }
+        Ok(self.recv_list.remove(0).into_raw())
+    }
+}
+
+/// Builds a `ClientConnection` (backed by `TestStream`s) suitable for
+/// exercising `ClientConnection`'s own methods in isolation.
+pub fn build_mock_client_conn() -> ClientConnection<TestStream> {
+    ClientConnection::new()
+}
+
+/// Builds a bare `HttpConnection`, suitable for use as the `conn` parameter
+/// passed to `Session` callbacks in tests that don't care about its state.
+pub fn build_mock_http_conn() -> HttpConnection {
+    HttpConnection::new()
+}
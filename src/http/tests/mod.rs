@@ -0,0 +1,5 @@
+//! Test-only helpers shared across the `http` module's unit tests: a mock
+//! `Stream` implementation and mock frame sources/sinks that let the
+//! connection and session layers be exercised without a real transport.
+
+pub mod common;
@@ -0,0 +1,5 @@
+//! `solicit` is a low-level HTTP/2 implementation, exposing the framing layer,
+//! a generic connection abstraction, and session-level callbacks that client
+//! and server implementations can build on top of.
+
+pub mod http;